@@ -1,5 +1,7 @@
 use hdk::prelude::*;
 
+pub mod sharding;
+
 // :TODO: remove, use something from Holochain Open Dev
 pub fn entry_from_record<T: TryFrom<SerializedBytes, Error = SerializedBytesError>>(
     record: Record,
@@ -13,3 +15,31 @@ pub fn entry_from_record<T: TryFrom<SerializedBytes, Error = SerializedBytesErro
             "Malformed bytes"
         ))))?)
 }
+
+// the links of each input, fetched in a single call, in the order of `inputs`
+pub fn get_links_batch(inputs: Vec<GetLinksInput>) -> ExternResult<Vec<Vec<Link>>> {
+    HDK.with(|hdk| hdk.borrow().get_links(inputs))
+}
+
+// fetches every record in a single call instead of one network round trip each. The records are in the
+// order of `hashes`, `None` where the record couldn't be found
+pub fn get_records<H: Into<AnyDhtHash>>(hashes: Vec<H>) -> ExternResult<Vec<Option<Record>>> {
+    let get_inputs = hashes
+        .into_iter()
+        .map(|hash| GetInput::new(hash.into(), GetOptions::default()))
+        .collect();
+    HDK.with(|hdk| hdk.borrow().get(get_inputs))
+}
+
+// the records of the entries the links point to, fetched in a single call
+pub fn get_linked_records(links: Vec<Link>) -> ExternResult<Vec<Option<Record>>> {
+    let entry_hashes = links
+        .into_iter()
+        .map(|link| {
+            link.target.into_entry_hash().ok_or(wasm_error!(WasmErrorInner::Guest(
+                String::from("Invalid link target")
+            )))
+        })
+        .collect::<ExternResult<Vec<EntryHash>>>()?;
+    get_records(entry_hashes)
+}
@@ -0,0 +1,117 @@
+use std::collections::BTreeSet;
+
+use hdk::prelude::*;
+
+use crate::get_links_batch;
+
+// anchors are split into shards by the first byte of the hash they link to, so that the links
+// of one anchor are spread over 16 parts of the DHT rather than held by the agents closest to a single path
+pub const SHARD_COUNT: u8 = 16;
+
+pub fn shard_of(target: &AnyLinkableHash) -> String {
+    format!("{:x}", target.get_raw_32()[0] % SHARD_COUNT)
+}
+
+// `<base>.<shard>`, the path a link to `target` is made from
+pub fn shard_path(base: &str, target: &AnyLinkableHash) -> Path {
+    Path::from(format!("{}.{}", base, shard_of(target)))
+}
+
+pub fn shard_paths(base: &str) -> Vec<Path> {
+    (0..SHARD_COUNT)
+        .map(|shard| Path::from(format!("{}.{:x}", base, shard)))
+        .collect()
+}
+
+// every shard of `base`, preceded by `base` itself, which links were made from before sharding
+pub fn all_shard_paths(base: &str) -> Vec<Path> {
+    std::iter::once(Path::from(base)).chain(shard_paths(base)).collect()
+}
+
+pub fn create_sharded_link<T, E>(
+    base: &str,
+    target: impl Into<AnyLinkableHash>,
+    link_type: T,
+    tag: impl Into<LinkTag>,
+) -> ExternResult<ActionHash>
+where
+    ScopedLinkType: TryFrom<T, Error = E>,
+    WasmError: From<E>,
+{
+    let target = target.into();
+    create_link(shard_path(base, &target).path_entry_hash()?, target, link_type, tag)
+}
+
+// the links of every shard, and of the anchor from before sharding, fetched in one call. A target linked
+// from both, e.g. while its links are being migrated, is only returned once
+pub fn get_sharded_links(
+    base: &str,
+    link_type: impl LinkTypeFilterExt,
+    tag: Option<LinkTag>,
+) -> ExternResult<Vec<Link>> {
+    Ok(dedupe_by_target(get_links_from_each(all_shard_paths(base), link_type, tag)?))
+}
+
+// keeps the first link to each target
+pub fn dedupe_by_target(links: Vec<Link>) -> Vec<Link> {
+    let mut seen: BTreeSet<AnyLinkableHash> = BTreeSet::new();
+    links
+        .into_iter()
+        .filter(|link| seen.insert(link.target.clone()))
+        .collect()
+}
+
+fn get_links_from_each(
+    paths: Vec<Path>,
+    link_type: impl LinkTypeFilterExt,
+    tag: Option<LinkTag>,
+) -> ExternResult<Vec<Link>> {
+    let link_type = link_type.try_into_filter()?;
+    let inputs = paths
+        .into_iter()
+        .map(|path| {
+            Ok(GetLinksInput::new(
+                path.path_entry_hash()?.into(),
+                link_type.clone(),
+                tag.clone(),
+            ))
+        })
+        .collect::<ExternResult<Vec<GetLinksInput>>>()?;
+    Ok(get_links_batch(inputs)?.into_iter().flatten().collect())
+}
+
+// moves the links this agent made from `base` before sharding to their shards, returning how many links were
+// made from the shards. A target already linked from its shard isn't linked again, its old link is only deleted,
+// so running this twice doesn't duplicate links. Other agents' links are left for them to move, so that every
+// link keeps its author, and the links of an agent who never migrates stay on `base`, which reads keep scanning
+pub fn migrate_to_shards<T, E>(base: &str, link_type: T) -> ExternResult<usize>
+where
+    T: LinkTypeFilterExt + Clone,
+    ScopedLinkType: TryFrom<T, Error = E>,
+    WasmError: From<E>,
+{
+    let me = agent_info()?.agent_latest_pubkey;
+    let links: Vec<Link> = get_links(Path::from(base).path_entry_hash()?, link_type.clone(), None)?
+        .into_iter()
+        .filter(|link| link.author == me)
+        .collect();
+    let mut moved = 0;
+    let mut sharded_targets: BTreeSet<AnyLinkableHash> =
+        get_links_from_each(shard_paths(base), link_type.clone(), None)?
+            .into_iter()
+            .map(|link| link.target)
+            .collect();
+    for link in links {
+        delete_link(link.create_link_hash)?;
+        if sharded_targets.insert(link.target.clone()) {
+            create_link(
+                shard_path(base, &link.target).path_entry_hash()?,
+                link.target,
+                link_type.clone(),
+                link.tag,
+            )?;
+            moved += 1;
+        }
+    }
+    Ok(moved)
+}
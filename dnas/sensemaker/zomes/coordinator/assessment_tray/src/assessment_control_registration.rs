@@ -1,4 +1,6 @@
 use hdk::prelude::*;
+use nh_sensemaker_zome_lib::get_linked_records;
+use nh_sensemaker_zome_lib::sharding::{create_sharded_link, get_sharded_links};
 use sensemaker_integrity_structs::{AssessmentControlRegistration, AssessmentControlRegistrationInput};
 use nh_zome_assessment_tray_integrity::*;
 
@@ -13,12 +15,7 @@ fn register_assessment_control(registration_input: AssessmentControlRegistration
     let eh = hash_entry(EntryTypes::AssessmentControlRegistration(input.clone()))?;
     // Create link
     // - control registration anchor to new entry hash
-    create_link(
-        registrations_typed_path()?.path_entry_hash()?,
-        eh.clone(),
-        LinkTypes::AssessmentControlRegistration,
-        (),
-    )?;
+    create_sharded_link(CONTROL_REGISTRATIONS_BASE, eh.clone(), LinkTypes::AssessmentControlRegistration, ())?;
 
     let record = get(action_hash.clone(), GetOptions::default())?
         .ok_or(wasm_error!(WasmErrorInner::Guest("AssessmentControlRegistration could not be retrieved after creation".into())))?;
@@ -40,32 +37,11 @@ fn get_assessment_control_registration(assessment_control_registration_eh: Entry
 
 #[hdk_extern]
 fn get_assessment_control_registrations(_:()) -> ExternResult<Vec<Record>> {
-    let links = get_links(
-        registrations_typed_path()?.path_entry_hash()?,
-        LinkTypes::AssessmentControlRegistration,
-        None,
-    )?;
-    match links.last() {
-        Some(_link) => {
-            let collected_get_results: ExternResult<Vec<Option<Record>>> = links.into_iter().map(|link| {
-                let entry_hash = link.target.into_entry_hash()
-                    .ok_or_else(|| wasm_error!(WasmErrorInner::Guest(String::from("Invalid link target"))))?;
-
-                get_assessment_control_registration(entry_hash)
-            }).collect();
-
-            // Handle the Result and then filter_map to remove None values
-            collected_get_results.map(|maybe_records| {
-                maybe_records.into_iter().filter_map(|maybe_record| maybe_record).collect::<Vec<Record>>()
-            })
-        }
-        None => Ok(vec![])
-    }
+    let links = get_sharded_links(CONTROL_REGISTRATIONS_BASE, LinkTypes::AssessmentControlRegistration, None)?;
+    Ok(get_linked_records(links)?.into_iter().flatten().collect())
 }
 
-fn registrations_typed_path() -> ExternResult<TypedPath> {
-    Path::from("control_registration").typed(LinkTypes::AssessmentControlRegistration)
-}
+pub const CONTROL_REGISTRATIONS_BASE: &str = "control_registration";
 
 #[hdk_extern]
 fn delete_assessment_control_registration(action_hash: ActionHash) -> ExternResult<ActionHash> {
@@ -1,4 +1,5 @@
 use hdk::prelude::*;
+use nh_sensemaker_zome_lib::sharding::{create_sharded_link, get_sharded_links};
 use nh_sensemaker_zome_lib::entry_from_record;
 use nh_zome_assessment_tray_integrity::*;
 
@@ -54,11 +55,7 @@ fn get_assessment_tray_config(assessment_tray_eh: EntryHash) -> ExternResult<Opt
 
 #[hdk_extern]
 fn get_assessment_tray_configs(_:()) -> ExternResult<Vec<Record>> {
-    let links = get_links(
-        tray_configs_typed_path()?.path_entry_hash()?,
-        LinkTypes::AssessmentTrayConfig,
-        None,
-    )?;
+    let links = get_sharded_links(TRAY_CONFIGS_BASE, LinkTypes::AssessmentTrayConfig, None)?;
     match links.last() {
         Some(_link) => {
             let collected_get_results: ExternResult<Vec<Option<Record>>> = links.into_iter().map(|link| {
@@ -104,17 +101,13 @@ impl TryFrom<AssessmentTrayConfigInput> for AssessmentTrayConfig {
 
 #[hdk_extern]
 fn set_assessment_tray_config(tray_config_input: AssessmentTrayConfigInput) -> ExternResult<Record> {
+    refresh_role_checkpoint()?;
     let input: AssessmentTrayConfig = tray_config_input.clone().try_into()?;
     let action_hash = create_entry(&EntryTypes::AssessmentTrayConfig(input.clone()))?;
 
     let eh = hash_entry(EntryTypes::AssessmentTrayConfig(input.clone()))?;
 
-    create_link(
-        tray_configs_typed_path()?.path_entry_hash()?,
-        eh.clone(),
-        LinkTypes::AssessmentTrayConfig,
-        (),
-    )?;
+    create_sharded_link(TRAY_CONFIGS_BASE, eh.clone(), LinkTypes::AssessmentTrayConfig, ())?;
 
     let record = get(action_hash.clone(), GetOptions::default())?
         .ok_or(wasm_error!(WasmErrorInner::Guest("AssessmentTrayConfig could not be retrieved after creation".into())))?;
@@ -124,6 +117,7 @@ fn set_assessment_tray_config(tray_config_input: AssessmentTrayConfigInput) -> E
 
 #[hdk_extern]
 fn update_assessment_tray_config(input: AssessmentTrayConfigUpdateInput) -> ExternResult<EntryHash> {
+    refresh_role_checkpoint()?;
     let updated_tray: AssessmentTrayConfig = input.updated_assessment_tray_config.clone().try_into()?;
     let _action_hash =update_entry(input.original_action_hash, updated_tray);
 
@@ -169,6 +163,7 @@ pub struct SetAssessmentTrayDefaultInput {
 
 #[hdk_extern]
 fn set_default_assessment_tray_config_for_resource_def(SetAssessmentTrayDefaultInput {resource_def_eh, assessment_tray_eh}: SetAssessmentTrayDefaultInput) -> ExternResult<EntryHash> {
+    refresh_role_checkpoint()?;
     let links = get_links(
         resource_def_eh.clone(),
         LinkTypes::ResourceDefDefaultAssessmentTrayConfig,
@@ -189,6 +184,21 @@ fn set_default_assessment_tray_config_for_resource_def(SetAssessmentTrayDefaultI
     Ok(assessment_tray_eh)
 }
 
-fn tray_configs_typed_path() -> ExternResult<TypedPath> {
-    Path::from("assessment_tray_config").typed(LinkTypes::AssessmentTrayConfig)
-}
\ No newline at end of file
+// tray configs need a role, so the sensemaker zome checkpoints the community activator's chain first
+fn refresh_role_checkpoint() -> ExternResult<()> {
+    match call(
+        CallTargetCell::Local,
+        ZomeName::from("sensemaker"),
+        "refresh_role_checkpoint".into(),
+        None,
+        (),
+    )? {
+        ZomeCallResponse::Ok(_) => Ok(()),
+        other => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "not able to refresh the role checkpoint: {:?}",
+            other
+        )))),
+    }
+}
+
+pub const TRAY_CONFIGS_BASE: &str = "assessment_tray_config";
\ No newline at end of file
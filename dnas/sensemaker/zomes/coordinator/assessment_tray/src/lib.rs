@@ -1,5 +1,6 @@
 mod assessment_tray_config;
 mod assessment_control_registration;
+mod migration;
 
 pub use assessment_tray_config::*;
 pub use assessment_control_registration::*;
@@ -0,0 +1,14 @@
+use hdk::prelude::*;
+use nh_sensemaker_zome_lib::sharding::migrate_to_shards;
+use nh_zome_assessment_tray_integrity::*;
+
+use crate::{CONTROL_REGISTRATIONS_BASE, TRAY_CONFIGS_BASE};
+
+// moves the links this agent made from the single anchors used before sharding to their shards, returning how
+// many links were made from the shards
+#[hdk_extern]
+fn migrate_sharded_anchors(_: ()) -> ExternResult<u32> {
+    let moved = migrate_to_shards(CONTROL_REGISTRATIONS_BASE, LinkTypes::AssessmentControlRegistration)?
+        + migrate_to_shards(TRAY_CONFIGS_BASE, LinkTypes::AssessmentTrayConfig)?;
+    Ok(moved as u32)
+}
@@ -1,21 +1,13 @@
 use hdk::prelude::*;
+use nh_sensemaker_zome_lib::sharding::get_sharded_links;
 use sensemaker_integrity::LinkTypes;
 
-const ALL_AGENTS_PATH: &str = "all_agents";
-
-pub fn all_agents_typed_path() -> ExternResult<TypedPath> {
-    Ok(Path::from(format!("{}", ALL_AGENTS_PATH)).typed(LinkTypes::AllAgentsPath)?)
-}
+pub const ALL_AGENTS_BASE: &str = "all_agents";
 
 #[hdk_extern]
 pub fn get_all_agents(_: ()) -> ExternResult<Vec<AgentPubKey>> {
     let my_pub_key = agent_info()?.agent_initial_pubkey;
-    let all_agents_path = all_agents_typed_path()?;
-    let all_agents_links = get_links(
-        all_agents_path.path_entry_hash()?,
-        LinkTypes::AllAgentsPath,
-        None,
-    )?;
+    let all_agents_links = get_sharded_links(ALL_AGENTS_BASE, LinkTypes::AllAgentsPath, None)?;
     let all_agents: Vec<AgentPubKey> = all_agents_links
         .into_iter()
         .filter_map(|link| link.target.into_agent_pub_key())
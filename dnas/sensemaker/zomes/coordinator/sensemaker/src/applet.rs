@@ -1,24 +1,30 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use hdk::prelude::*;
 use sensemaker_integrity::{
-    AppletConfig, AppletConfigInput, CulturalContext, Dimension, EntryTypes, LinkTypes, Method,
-    ResourceDef,
+    first_applet_config_version, AppletConfig, AppletConfigInput, Assessment, ConfigProblem,
+    CulturalContext, Dimension, EntryTypes, LinkTypes, Method, Range, ResourceDef, Role,
 };
 
+use nh_sensemaker_zome_lib::sharding::get_sharded_links;
+
 use crate::{
-    create_cultural_context, create_method, create_range, create_resource_def,
-    utils::{entry_from_record, entry_hash_from_record},
+    agent_has_role, assessment_hour, assessment_time_bucket_typed_path, assessment_typed_path,
+    create_cultural_context, create_dimension, create_method, create_range, create_resource_def,
+    cultural_contexts_typed_path, legacy_assessment_typed_path, refresh_role_checkpoint,
+    utils::{
+        entry_from_record, entry_hash_from_record, get_linked_records, get_links_batch,
+        get_records,
+    },
+    DIMENSIONS_BASE, METHODS_BASE, RANGES_BASE, RESOURCE_DEFS_BASE,
 };
 
 #[hdk_extern]
 pub fn register_applet(
     applet_config_input: AppletConfigInput
 ) -> ExternResult<AppletConfig> {
-    // check the format of the applet config
-    if let Err(err) = applet_config_input.clone().check_format() {
-        debug!("Config format incorrect: {:?}", err)
-    }
+    // reject the config with every problem found, nothing is committed for a malformed config
+    applet_config_input.clone().check_format()?;
     // check that it doesn't already exist
     let maybe_applet_config = check_if_applet_config_exists(applet_config_input.name.clone())?;
     if let Some(applet_config) = maybe_applet_config {
@@ -32,35 +38,538 @@ pub fn register_applet(
     }
 }
 
+// lists every problem with a config without registering it, for applet developers to check their config
+#[hdk_extern]
+pub fn check_applet_config(applet_config_input: AppletConfigInput) -> ExternResult<Vec<ConfigProblem>> {
+    applet_config_input.problems()
+}
+
 #[hdk_extern]
 pub fn check_if_applet_config_exists(applet_name: String) -> ExternResult<Option<AppletConfig>> {
+    Ok(get_latest_applet_config(applet_name)?.map(|(applet_config, _)| applet_config))
+}
+
+// diffs the new config against the latest version, creating only the entries that are new or changed,
+// and records the result as the next version of the config
+#[hdk_extern]
+pub fn update_applet_config(applet_config_input: AppletConfigInput) -> ExternResult<AppletConfig> {
+    applet_config_input.clone().check_format()?;
+    let (previous_config, previous_config_eh) =
+        get_latest_applet_config(applet_config_input.name.clone())?.ok_or(wasm_error!(
+            WasmErrorInner::Guest(format!(
+                "applet config {} is not registered",
+                applet_config_input.name
+            ))
+        ))?;
+    if previous_config.applet_eh != applet_config_input.applet_eh {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "applet config {} is registered for a different applet",
+            applet_config_input.name
+        ))));
+    }
+
+    let ranges = migrate_entries(
+        &previous_config.ranges,
+        applet_config_input
+            .ranges
+            .into_iter()
+            .map(|range| (range.name.clone(), range))
+            .collect(),
+        |range: &Range| hash_entry(range.clone()),
+        create_range,
+    )?;
+    let dimensions = migrate_entries(
+        &previous_config.dimensions,
+        applet_config_input
+            .dimensions
+            .into_iter()
+            .map(|dimension| Ok((dimension.name.clone(), Dimension::try_from(dimension)?)))
+            .collect::<ExternResult<Vec<(String, Dimension)>>>()?,
+        |dimension: &Dimension| hash_entry(dimension.clone()),
+        create_dimension,
+    )?;
+    let resource_defs = migrate_entries(
+        &previous_config.resource_defs,
+        applet_config_input
+            .resource_defs
+            .into_iter()
+            .map(|resource_def| {
+                Ok((resource_def.resource_name.clone(), ResourceDef::try_from(resource_def)?))
+            })
+            .collect::<ExternResult<Vec<(String, ResourceDef)>>>()?,
+        |resource_def: &ResourceDef| hash_entry(resource_def.clone()),
+        create_resource_def,
+    )?;
+    let methods = migrate_entries(
+        &previous_config.methods,
+        applet_config_input
+            .methods
+            .into_iter()
+            .map(|method| Ok((method.name.clone(), Method::try_from(method)?)))
+            .collect::<ExternResult<Vec<(String, Method)>>>()?,
+        |method: &Method| hash_entry(method.clone()),
+        create_method,
+    )?;
+    // a method the new version drops or replaces is unlinked, so that it isn't listed or run live alongside
+    // its replacement
+    let kept_method_ehs: BTreeSet<EntryHash> = methods
+        .values()
+        .cloned()
+        .chain(entry_hashes_of_other_applets(&previous_config.name)?)
+        .collect();
+    let superseded_method_ehs: BTreeSet<EntryHash> = previous_config
+        .methods
+        .values()
+        .filter(|method_eh| !kept_method_ehs.contains(method_eh))
+        .cloned()
+        .collect();
+    unlink_methods(&superseded_method_ehs)?;
+    let cultural_contexts = migrate_entries(
+        &previous_config.cultural_contexts,
+        applet_config_input
+            .cultural_contexts
+            .into_iter()
+            .map(|context| Ok((context.name.clone(), CulturalContext::try_from(context)?)))
+            .collect::<ExternResult<Vec<(String, CulturalContext)>>>()?,
+        |context: &CulturalContext| hash_entry(context.clone()),
+        create_cultural_context,
+    )?;
+
+    if ranges == previous_config.ranges
+        && dimensions == previous_config.dimensions
+        && resource_defs == previous_config.resource_defs
+        && methods == previous_config.methods
+        && cultural_contexts == previous_config.cultural_contexts
+    {
+        // nothing changed, no new version
+        return Ok(previous_config);
+    }
+
+    let applet_config = AppletConfig {
+        name: previous_config.name,
+        applet_eh: previous_config.applet_eh,
+        ranges,
+        dimensions,
+        resource_defs,
+        methods,
+        cultural_contexts,
+        version: previous_config.version + 1,
+        previous_config_eh: Some(previous_config_eh),
+    };
+    commit_applet_config(applet_config.clone())?;
+    Ok(applet_config)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetAppletConfigAtVersionInput {
+    name: String,
+    version: u32,
+}
+
+// walks the history chain back from the latest version
+#[hdk_extern]
+pub fn get_applet_config_at_version(
+    input: GetAppletConfigAtVersionInput,
+) -> ExternResult<Option<AppletConfig>> {
+    let mut maybe_applet_config =
+        get_latest_applet_config(input.name)?.map(|(applet_config, _)| applet_config);
+    while let Some(applet_config) = maybe_applet_config {
+        if applet_config.version == input.version {
+            return Ok(Some(applet_config));
+        }
+        if applet_config.version < input.version {
+            return Ok(None);
+        }
+        maybe_applet_config = match applet_config.previous_config_eh {
+            Some(previous_config_eh) => Some(get_applet_config(previous_config_eh)?),
+            None => None,
+        };
+    }
+    Ok(None)
+}
+
+#[hdk_extern]
+pub fn get_applet_configs(_: ()) -> ExternResult<Vec<Record>> {
+    let link_type = LinkTypes::AppletConfig.try_into_filter()?;
+    let inputs = all_applets_typed_path()?
+        .children_paths()?
+        .into_iter()
+        .map(|applet_path| {
+            Ok(GetLinksInput::new(applet_path.path_entry_hash()?.into(), link_type.clone(), None))
+        })
+        .collect::<ExternResult<Vec<GetLinksInput>>>()?;
+    let links = get_links_batch(inputs)?.into_iter().flatten().collect();
+
+    // every version of every applet's config is fetched at once, and the latest kept for each applet
+    let mut latest_configs: BTreeMap<String, (u32, Record)> = BTreeMap::new();
+    for record in get_applet_config_records(links)? {
+        let applet_config = entry_from_record::<AppletConfig>(record.clone())?;
+        let is_later = match latest_configs.get(&applet_config.name) {
+            Some((latest_version, _)) => applet_config.version > *latest_version,
+            None => true,
+        };
+        if is_later {
+            latest_configs.insert(applet_config.name, (applet_config.version, record));
+        }
+    }
+    Ok(latest_configs.into_values().map(|(_, record)| record).collect())
+}
+
+#[hdk_extern]
+pub fn get_applet_config_for_resource_def(resource_def_eh: EntryHash) -> ExternResult<Option<Record>> {
+    let links = get_links(resource_def_eh, LinkTypes::ResourceDefEhToAppletConfig, None)?;
+    get_latest_applet_config_from_links(links)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UnregisterAppletInput {
+    applet_name: String,
+    // keep the assessments along the applet's dimensions for archival rather than deleting them
+    keep_assessments: bool,
+}
+
+// deletes every version of the config and the entries and links created for it, so that the applet
+// no longer shows up in the read APIs. The deleted entries stay retrievable with `get_details`.
+// Entries that another registered applet's config also uses are kept.
+#[hdk_extern]
+pub fn unregister_applet(input: UnregisterAppletInput) -> ExternResult<()> {
+    refresh_role_checkpoint(())?;
+    if !agent_has_role(&Role::Steward)? {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "only the community activator or a steward can unregister an applet"
+        ))));
+    }
+    let config_links = get_links(
+        applet_config_typed_path(input.applet_name.clone())?.path_entry_hash()?,
+        LinkTypes::AppletConfig,
+        None,
+    )?;
+    if config_links.is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "applet config {} is not registered",
+            input.applet_name
+        ))));
+    }
+
+    // entries used by the other applets are left alone
+    let shared_ehs = entry_hashes_of_other_applets(&input.applet_name)?;
+
+    let mut versions: Vec<AppletConfig> = vec![];
+    let mut version_ehs: BTreeSet<EntryHash> = BTreeSet::new();
+    for record in get_applet_config_records(config_links.clone())? {
+        versions.push(entry_from_record::<AppletConfig>(record.clone())?);
+        version_ehs.insert(entry_hash_from_record(record)?);
+    }
+    for link in config_links {
+        delete_link(link.create_link_hash)?;
+    }
+    let all_resource_def_ehs =
+        entry_hashes_of(&versions, |applet_config| &applet_config.resource_defs);
+    let unshared = |ehs: BTreeSet<EntryHash>| {
+        ehs.difference(&shared_ehs).cloned().collect::<BTreeSet<EntryHash>>()
+    };
+    let range_ehs = unshared(entry_hashes_of(&versions, |applet_config| &applet_config.ranges));
+    let dimension_ehs = unshared(entry_hashes_of(&versions, |applet_config| &applet_config.dimensions));
+    let resource_def_ehs = unshared(all_resource_def_ehs.clone());
+    let method_ehs = unshared(entry_hashes_of(&versions, |applet_config| &applet_config.methods));
+    let context_ehs = unshared(entry_hashes_of(&versions, |applet_config| {
+        &applet_config.cultural_contexts
+    }));
+
+    // links
+    delete_links_to(get_sharded_links(RANGES_BASE, LinkTypes::Ranges, None)?, &range_ehs)?;
+    delete_links_to(
+        get_sharded_links(DIMENSIONS_BASE, LinkTypes::Dimensions, None)?,
+        &dimension_ehs,
+    )?;
+    delete_links_to(
+        get_sharded_links(RESOURCE_DEFS_BASE, LinkTypes::ResourceDefs, None)?,
+        &resource_def_ehs,
+    )?;
+    unlink_methods(&method_ehs)?;
+    for resource_def_eh in all_resource_def_ehs.iter().cloned() {
+        delete_links_to(
+            get_links(resource_def_eh.clone(), LinkTypes::ResourceDefEhToAppletConfig, None)?,
+            &version_ehs,
+        )?;
+        delete_links_to(
+            get_links(resource_def_eh, LinkTypes::ResourceDefToCulturalContext, None)?,
+            &context_ehs,
+        )?;
+    }
+    let applet_ehs = versions
+        .iter()
+        .map(|applet_config| applet_config.applet_eh.clone())
+        .collect::<BTreeSet<EntryHash>>();
+    for applet_eh in applet_ehs {
+        delete_links_to(
+            get_links(
+                cultural_contexts_typed_path(applet_eh)?.path_entry_hash()?,
+                LinkTypes::CulturalContexts,
+                None,
+            )?,
+            &context_ehs,
+        )?;
+    }
+
+    if !input.keep_assessments {
+        delete_assessments(&dimension_ehs, &resource_def_ehs, &all_resource_def_ehs)?;
+    }
+
+    // entries
+    for entry_hash in version_ehs
+        .into_iter()
+        .chain(context_ehs)
+        .chain(method_ehs)
+        .chain(resource_def_ehs)
+        .chain(dimension_ehs)
+        .chain(range_ehs)
+    {
+        delete_entry_creates(entry_hash)?;
+    }
+    Ok(())
+}
+
+// every entry hash of one kind across all versions of a config
+fn entry_hashes_of(
+    versions: &[AppletConfig],
+    select: impl Fn(&AppletConfig) -> &BTreeMap<String, EntryHash>,
+) -> BTreeSet<EntryHash> {
+    versions
+        .iter()
+        .flat_map(|applet_config| select(applet_config).values().cloned())
+        .collect()
+}
+
+// the entry hashes used by the latest version of every other applet config
+fn entry_hashes_of_other_applets(applet_name: &str) -> ExternResult<BTreeSet<EntryHash>> {
+    let mut entry_hashes: BTreeSet<EntryHash> = BTreeSet::new();
+    for record in get_applet_configs(())? {
+        let applet_config = entry_from_record::<AppletConfig>(record)?;
+        if applet_config.name != applet_name {
+            entry_hashes.extend(applet_config_entry_hashes(&applet_config));
+        }
+    }
+    Ok(entry_hashes)
+}
+
+fn applet_config_entry_hashes(applet_config: &AppletConfig) -> Vec<EntryHash> {
+    applet_config
+        .ranges
+        .values()
+        .chain(applet_config.dimensions.values())
+        .chain(applet_config.resource_defs.values())
+        .chain(applet_config.methods.values())
+        .chain(applet_config.cultural_contexts.values())
+        .cloned()
+        .collect()
+}
+
+// unlinks the methods from the methods anchor and from their input and output dimensions
+fn unlink_methods(method_ehs: &BTreeSet<EntryHash>) -> ExternResult<()> {
+    if method_ehs.is_empty() {
+        return Ok(());
+    }
+    let mut dimension_ehs: BTreeSet<EntryHash> = BTreeSet::new();
+    for record in get_records(method_ehs.iter().cloned().collect())?.into_iter().flatten() {
+        let method = entry_from_record::<Method>(record)?;
+        dimension_ehs.insert(method.output_dimension_eh);
+        dimension_ehs.extend(method.input_dimension_ehs);
+    }
+    delete_links_to(get_sharded_links(METHODS_BASE, LinkTypes::Method, None)?, method_ehs)?;
+    let link_type = LinkTypes::DimensionToMethod.try_into_filter()?;
+    let inputs = dimension_ehs
+        .into_iter()
+        .map(|dimension_eh| GetLinksInput::new(dimension_eh.into(), link_type.clone(), None))
+        .collect();
+    delete_links_to(get_links_batch(inputs)?.into_iter().flatten().collect(), method_ehs)
+}
+
+// deletes the links to any of the targets, once each when the same link was read more than once
+fn delete_links_to(links: Vec<Link>, targets: &BTreeSet<EntryHash>) -> ExternResult<()> {
+    let mut deleted: BTreeSet<ActionHash> = BTreeSet::new();
+    for link in links {
+        if let Some(target) = link.target.into_entry_hash() {
+            if targets.contains(&target) && deleted.insert(link.create_link_hash.clone()) {
+                delete_link(link.create_link_hash)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// deletes every action that created the entry, so that `get` no longer returns it
+fn delete_entry_creates(entry_hash: EntryHash) -> ExternResult<()> {
+    if let Some(Details::Entry(details)) = get_details(entry_hash, GetOptions::default())? {
+        if let EntryDhtStatus::Live = details.entry_dht_status {
+            for action in details.actions {
+                delete_entry(action.as_hash().clone())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// unlinks the assessments along the given dimensions or of the given resource defs, from the assessment tree
+// and from the indexes they are listed in. They are found through the resource def index: every assessment of
+// the resource defs, and the assessments along the dimensions on the other resource defs the applet has used.
+// Only their author can delete assessments, so this agent's own are deleted and the others stay on the DHT unlinked
+fn delete_assessments(
+    dimension_ehs: &BTreeSet<EntryHash>,
+    resource_def_ehs: &BTreeSet<EntryHash>,
+    all_resource_def_ehs: &BTreeSet<EntryHash>,
+) -> ExternResult<()> {
+    let me = agent_info()?.agent_latest_pubkey;
+    let link_type = LinkTypes::ResourceDefToAssessment.try_into_filter()?;
+    let mut inputs = vec![];
+    for resource_def_eh in resource_def_ehs {
+        inputs.push(GetLinksInput::new(resource_def_eh.clone().into(), link_type.clone(), None));
+    }
+    for resource_def_eh in all_resource_def_ehs.difference(resource_def_ehs) {
+        for dimension_eh in dimension_ehs {
+            inputs.push(GetLinksInput::new(
+                resource_def_eh.clone().into(),
+                link_type.clone(),
+                Some(LinkTag::new(dimension_eh.to_string())),
+            ));
+        }
+    }
+    let links: Vec<Link> = get_links_batch(inputs)?.into_iter().flatten().collect();
+    let mut unlinked_assessments: BTreeMap<EntryHash, Assessment> = BTreeMap::new();
+    for record in get_linked_records(links)?.into_iter().flatten() {
+        let assessment_eh = entry_hash_from_record(record.clone())?;
+        let assessment = entry_from_record::<Assessment>(record)?;
+        if assessment.author == me && !unlinked_assessments.contains_key(&assessment_eh) {
+            delete_entry_creates(assessment_eh.clone())?;
+        }
+        unlinked_assessments.insert(assessment_eh, assessment);
+    }
+    delete_assessment_index_links(unlinked_assessments)
+}
+
+// the assessments are linked from their `<resource>.<dimension>` paths, in their shard and in the legacy tree,
+// the index links are tagged with the assessment's dimension and the time buckets hold an hour of assessments,
+// so only those are read, in a single call
+fn delete_assessment_index_links(assessments: BTreeMap<EntryHash, Assessment>) -> ExternResult<()> {
+    let mut inputs = vec![];
+    for assessment in assessments.values() {
+        for assessment_path in [
+            assessment_typed_path(assessment.resource_eh.clone(), assessment.dimension_eh.clone())?,
+            legacy_assessment_typed_path(assessment.resource_eh.clone(), assessment.dimension_eh.clone())?,
+        ] {
+            inputs.push(GetLinksInput::new(
+                assessment_path.path_entry_hash()?.into(),
+                LinkTypes::Assessment.try_into_filter()?,
+                None,
+            ));
+        }
+        let dimension_tag = Some(LinkTag::new(assessment.dimension_eh.to_string()));
+        inputs.push(GetLinksInput::new(
+            assessment.author.clone().into(),
+            LinkTypes::AgentToAssessment.try_into_filter()?,
+            dimension_tag.clone(),
+        ));
+        inputs.push(GetLinksInput::new(
+            assessment.resource_def_eh.clone().into(),
+            LinkTypes::ResourceDefToAssessment.try_into_filter()?,
+            dimension_tag,
+        ));
+        let time_bucket_path = assessment_time_bucket_typed_path(
+            assessment.resource_def_eh.clone(),
+            assessment_hour(&assessment.timestamp),
+        )?;
+        inputs.push(GetLinksInput::new(
+            time_bucket_path.path_entry_hash()?.into(),
+            LinkTypes::AssessmentTimeBuckets.try_into_filter()?,
+            None,
+        ));
+    }
+    let assessment_ehs: BTreeSet<EntryHash> = assessments.into_keys().collect();
+    delete_links_to(get_links_batch(inputs)?.into_iter().flatten().collect(), &assessment_ehs)
+}
+
+// the config with the highest version, along with its entry hash
+fn get_latest_applet_config(applet_name: String) -> ExternResult<Option<(AppletConfig, EntryHash)>> {
     let links = get_links(
         applet_config_typed_path(applet_name)?.path_entry_hash()?,
         LinkTypes::AppletConfig,
         None,
     )?;
-    let maybe_last_link = links.last();
+    match get_latest_applet_config_from_links(links)? {
+        Some(record) => Ok(Some((
+            entry_from_record::<AppletConfig>(record.clone())?,
+            entry_hash_from_record(record)?,
+        ))),
+        None => Ok(None),
+    }
+}
 
-    if let Some(link) = maybe_last_link {
-        let maybe_record = get(link.target.clone().into_entry_hash()
-            .ok_or(wasm_error!(WasmErrorInner::Guest(String::from("Invalid link target"))))?,
-            GetOptions::default()
-        )?;
-        if let Some(record) = maybe_record {
-            Ok(Some(entry_from_record::<AppletConfig>(record)?))
+// every version of a config is linked, so only keep the record with the highest version
+fn get_latest_applet_config_from_links(links: Vec<Link>) -> ExternResult<Option<Record>> {
+    let mut maybe_latest: Option<(u32, Record)> = None;
+    for record in get_applet_config_records(links)? {
+        let version = entry_from_record::<AppletConfig>(record.clone())?.version;
+        let is_later = match &maybe_latest {
+            Some((latest_version, _)) => version > *latest_version,
+            None => true,
+        };
+        if is_later {
+            maybe_latest = Some((version, record));
+        }
+    }
+    Ok(maybe_latest.map(|(_, record)| record))
+}
+
+fn get_applet_config(applet_config_eh: EntryHash) -> ExternResult<AppletConfig> {
+    entry_from_record::<AppletConfig>(get_applet_config_record(applet_config_eh)?)
+}
+
+fn get_applet_config_record(applet_config_eh: EntryHash) -> ExternResult<Record> {
+    get(applet_config_eh, GetOptions::default())?.ok_or_else(applet_config_not_found)
+}
+
+// the records of the applet configs the links point to, fetched in a single call
+fn get_applet_config_records(links: Vec<Link>) -> ExternResult<Vec<Record>> {
+    get_linked_records(links)?
+        .into_iter()
+        .map(|maybe_record| maybe_record.ok_or_else(applet_config_not_found))
+        .collect()
+}
+
+fn applet_config_not_found() -> WasmError {
+    wasm_error!(WasmErrorInner::Guest(String::from(
+        "unable to get applet config entry from entry hash"
+    )))
+}
+
+// reuses the entry hash of every entry the previous version already has under the same name,
+// and only creates the entries that are new or have changed
+fn migrate_entries<T>(
+    previous: &BTreeMap<String, EntryHash>,
+    entries: Vec<(String, T)>,
+    hash: impl Fn(&T) -> ExternResult<EntryHash>,
+    create: impl Fn(T) -> ExternResult<Record>,
+) -> ExternResult<BTreeMap<String, EntryHash>> {
+    let mut migrated: BTreeMap<String, EntryHash> = BTreeMap::new();
+    for (name, entry) in entries {
+        let entry_eh = hash(&entry)?;
+        if previous.get(&name) == Some(&entry_eh) {
+            migrated.insert(name, entry_eh);
         } else {
-            Err(wasm_error!(WasmErrorInner::Guest(String::from(
-                "unable to get applet config entry from entry hash"
-            ))))
+            migrated.insert(name, entry_hash_from_record(create(entry)?)?);
         }
-    } else {
-        // config doesn't exist
-        Ok(None)
     }
+    Ok(migrated)
+}
+
+const ALL_APPLETS_BASE: &str = "all_applets";
+
+fn all_applets_typed_path() -> ExternResult<TypedPath> {
+    Path::from(ALL_APPLETS_BASE).typed(LinkTypes::AppletName)
 }
 
 fn applet_config_typed_path(applet_name: String) -> ExternResult<TypedPath> {
-    Ok(Path::from(format!("all_applets.{}", applet_name)).typed(LinkTypes::AppletName)?)
+    Ok(Path::from(format!("{}.{}", ALL_APPLETS_BASE, applet_name)).typed(LinkTypes::AppletName)?)
 }
 
 // create all entries specified in the config
@@ -73,8 +582,14 @@ pub fn create_entries_from_applet_config(
         ranges.insert(range.name.clone(), entry_hash_from_record(create_range(range)?)?);
     }
 
-    // dimensions -- pass an empty vector as dimensions needn't always be accepted in the config
-    let dimensions: BTreeMap<String, EntryHash> = BTreeMap::new();
+    // dimensions
+    let mut dimensions: BTreeMap<String, EntryHash> = BTreeMap::new();
+    for config_dimension in config.dimensions {
+        dimensions.insert(
+            config_dimension.name.clone(),
+            entry_hash_from_record(create_dimension(Dimension::try_from(config_dimension)?)?)?,
+        );
+    }
 
     // resource defs
     let mut resource_defs: BTreeMap<String, EntryHash> = BTreeMap::new();
@@ -85,8 +600,14 @@ pub fn create_entries_from_applet_config(
         );
     }
 
-    // methods -- pass an empty vector as output dimension dependent methods needn't always be accepted in the config
+    // methods -- created after the dimensions and ranges, as their programs are type checked against them
     let mut methods: BTreeMap<String, EntryHash> = BTreeMap::new();
+    for config_method in config.methods {
+        methods.insert(
+            config_method.name.clone(),
+            entry_hash_from_record(create_method(Method::try_from(config_method)?)?)?,
+        );
+    }
 
     // CCs
     let mut cultural_contexts: BTreeMap<String, EntryHash> = BTreeMap::new();
@@ -102,10 +623,19 @@ pub fn create_entries_from_applet_config(
         applet_eh: config.applet_eh,
         ranges,
         dimensions,
-        resource_defs: resource_defs.clone(),
+        resource_defs,
         methods,
         cultural_contexts,
+        version: first_applet_config_version(),
+        previous_config_eh: None,
     };
+    let applet_config_eh = commit_applet_config(applet_config.clone())?;
+    Ok((applet_config, applet_config_eh))
+}
+
+// create the config entry and link it from its name and resource defs
+fn commit_applet_config(applet_config: AppletConfig) -> ExternResult<EntryHash> {
+    refresh_role_checkpoint(())?;
     create_entry(&EntryTypes::AppletConfig(applet_config.clone()))?;
     let applet_config_eh = hash_entry(&EntryTypes::AppletConfig(applet_config.clone()))?;
     let applet_config_path = applet_config_typed_path(applet_config.name.clone())?;
@@ -118,7 +648,8 @@ pub fn create_entries_from_applet_config(
         (),
     )?;
     // for each resource type entry hash, create a link
-    resource_defs
+    applet_config
+        .resource_defs
         .into_iter()
         .map(|(_, resource_def_eh)| {
             create_link(
@@ -129,5 +660,5 @@ pub fn create_entries_from_applet_config(
             )
         })
         .collect::<ExternResult<Vec<ActionHash>>>()?;
-    Ok((applet_config, applet_config_eh))
+    Ok(applet_config_eh)
 }
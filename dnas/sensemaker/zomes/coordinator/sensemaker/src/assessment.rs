@@ -1,6 +1,7 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use hdk::prelude::*;
+use nh_sensemaker_zome_lib::sharding::{dedupe_by_target, get_sharded_links, shard_of, shard_paths};
 use sensemaker_integrity::Assessment;
 use sensemaker_integrity::DataSet;
 use sensemaker_integrity::EntryTypes;
@@ -8,13 +9,23 @@ use sensemaker_integrity::LinkTypes;
 use sensemaker_integrity::RangeValue;
 
 use crate::agent::get_all_agents;
-use crate::dimensions_typed_path;
 use crate::signals::Signal;
 use crate::utils::entry_from_record;
 use crate::utils::flatten_btree_map;
-use crate::utils::get_assessments_for_resource_inner;
+use crate::utils::get_assessments_for_resources_inner;
+use crate::utils::get_linked_records;
+use crate::utils::get_links_batch;
+use crate::utils::get_links_from_paths;
+use crate::DIMENSIONS_BASE;
 
-const ALL_ASSESSED_RESOURCES_BASE: &str = "all_assessed_resources";
+const ASSESSED_RESOURCES_BASE: &str = "assessed_resources";
+// the unsharded tree assessments were linked from before sharding
+const LEGACY_ASSESSED_RESOURCES_BASE: &str = "all_assessed_resources";
+const ASSESSMENT_TIME_BUCKETS_BASE: &str = "assessment_time_buckets";
+const MICROS_PER_HOUR: i64 = 60 * 60 * 1_000_000;
+const HOURS_PER_DAY: i64 = 24;
+// the longest window `get_assessments_in_time_range` reads
+const MAX_TIME_RANGE_DAYS: i64 = 31;
 
 #[hdk_extern]
 pub fn get_assessment(entry_hash: EntryHash) -> ExternResult<Option<Record>> {
@@ -25,6 +36,8 @@ pub fn get_assessment(entry_hash: EntryHash) -> ExternResult<Option<Record>> {
 pub struct GetAssessmentsForResourceInput {
     resource_ehs: Option<Vec<EntryHash>>,
     dimension_ehs: Option<Vec<EntryHash>>,
+    // only assessments of resources of these resource defs
+    resource_def_ehs: Option<Vec<EntryHash>>,
 }
 
 
@@ -33,43 +46,73 @@ pub fn get_assessments_for_resources(
     GetAssessmentsForResourceInput {
         resource_ehs,
         dimension_ehs,
+        resource_def_ehs,
     }: GetAssessmentsForResourceInput,
 ) -> ExternResult<BTreeMap<String, Vec<Assessment>>> {
     let mut resource_assessments = BTreeMap::<String, Vec<Assessment>>::new();
-    let all_or_some_dimension_ehs: Vec<EntryHash>;
-    match dimension_ehs {
-        Some(dimension_ehs) => all_or_some_dimension_ehs = dimension_ehs,
-        None => {
-            all_or_some_dimension_ehs = get_links(
-                dimensions_typed_path()?.path_entry_hash()?,
-                LinkTypes::Dimensions,
-                None,
-            )?
-            .into_iter()
-            .filter_map(|link| link.target.into_entry_hash())
-            .collect();
-        }
-    }
-    match resource_ehs {
-        Some(resource_ehs) => {
-            for resource_eh in resource_ehs {
-                let assessments = get_assessments_for_resource_inner(resource_eh.clone(), all_or_some_dimension_ehs.clone())?;
-                resource_assessments.insert(resource_eh.to_string(), flatten_btree_map(assessments));
+    match (resource_ehs, resource_def_ehs) {
+        (Some(resource_ehs), resource_def_ehs) => {
+            let all_or_some_dimension_ehs: Vec<EntryHash>;
+            match dimension_ehs {
+                Some(dimension_ehs) => all_or_some_dimension_ehs = dimension_ehs,
+                None => {
+                    all_or_some_dimension_ehs = get_sharded_links(DIMENSIONS_BASE, LinkTypes::Dimensions, None)?
+                    .into_iter()
+                    .filter_map(|link| link.target.into_entry_hash())
+                    .collect();
+                }
+            }
+            for (resource_eh, assessments) in get_assessments_for_resources_inner(resource_ehs, all_or_some_dimension_ehs)? {
+                let assessments = flatten_btree_map(assessments)
+                    .into_iter()
+                    .filter(|assessment| is_of_resource_defs(assessment, &resource_def_ehs))
+                    .collect();
+                resource_assessments.insert(resource_eh.to_string(), assessments);
+            }
+        },
+        // only the resource defs' assessments are read
+        (None, Some(resource_def_ehs)) => {
+            for resource_def_eh in resource_def_ehs {
+                let assessments = get_dimension_tagged_assessments(
+                    resource_def_eh.clone(),
+                    LinkTypes::ResourceDefToAssessment,
+                    &dimension_ehs,
+                )?
+                .into_iter()
+                .filter(|assessment| assessment.resource_def_eh == resource_def_eh)
+                .collect();
+                group_by_resource(&mut resource_assessments, assessments);
             }
         },
-        None => {
-            // TODO: handle the case where all resources are requested but only along specific dimensions
-            let all_assessments = get_all_assessments(())?;
-            all_assessments.into_iter().for_each(|assessment| {
-                let resource_eh = assessment.resource_eh.clone();
-                let assessments = resource_assessments.entry(resource_eh.to_string()).or_insert(vec![]);
-                assessments.push(assessment);
-            });
+        (None, None) => {
+            let all_assessments = get_all_assessments(())?
+                .into_iter()
+                .filter(|assessment| {
+                    dimension_ehs
+                        .as_ref()
+                        .map_or(true, |dimension_ehs| dimension_ehs.contains(&assessment.dimension_eh))
+                })
+                .collect();
+            group_by_resource(&mut resource_assessments, all_assessments);
         }
     }
     Ok(resource_assessments)
 }
 
+fn is_of_resource_defs(assessment: &Assessment, resource_def_ehs: &Option<Vec<EntryHash>>) -> bool {
+    resource_def_ehs
+        .as_ref()
+        .map_or(true, |resource_def_ehs| resource_def_ehs.contains(&assessment.resource_def_eh))
+}
+
+fn group_by_resource(resource_assessments: &mut BTreeMap<String, Vec<Assessment>>, assessments: Vec<Assessment>) {
+    assessments.into_iter().for_each(|assessment| {
+        let resource_eh = assessment.resource_eh.clone();
+        let assessments = resource_assessments.entry(resource_eh.to_string()).or_insert(vec![]);
+        assessments.push(assessment);
+    });
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct CreateAssessmentInput {
     pub value: RangeValue,
@@ -106,6 +149,30 @@ pub fn create_assessment(CreateAssessmentInput { value, dimension_eh, resource_e
             LinkTypes::Assessment,
             (),
         )?;
+        let time_bucket_path = assessment_time_bucket_typed_path(
+            assessment.resource_def_eh.clone(),
+            assessment_hour(&assessment.timestamp),
+        )?;
+        time_bucket_path.ensure()?;
+        create_link(
+            time_bucket_path.path_entry_hash()?,
+            assessment_eh.clone(),
+            LinkTypes::AssessmentTimeBuckets,
+            (),
+        )?;
+        // index by author and by resource def, tagged with the dimension so that they can be filtered by dimension
+        create_link(
+            assessment.author.clone(),
+            assessment_eh.clone(),
+            LinkTypes::AgentToAssessment,
+            LinkTag::new(assessment.dimension_eh.to_string()),
+        )?;
+        create_link(
+            assessment.resource_def_eh.clone(),
+            assessment_eh.clone(),
+            LinkTypes::ResourceDefToAssessment,
+            LinkTag::new(assessment.dimension_eh.to_string()),
+        )?;
 
         // send signal after assessment is created
         let signal = Signal::NewAssessment { assessment: assessment.clone() };
@@ -121,36 +188,151 @@ pub fn create_assessment(CreateAssessmentInput { value, dimension_eh, resource_e
 
 #[hdk_extern]
 pub fn get_all_assessments(_:()) -> ExternResult<Vec<Assessment>> {
-    let base_path = all_assessments_typed_path()?;
-    let assessed_resources_typed_paths = base_path.children_paths()?;
-    let mut all_assessments: Vec<Vec<Assessment>> = vec![];
-
-    // for each resource that has been assessed, crawl all children to get each dimension that it has been assessed along
-    for assessed_resource_path in assessed_resources_typed_paths {
-        let assessed_dimensions_for_resource_typed_paths = assessed_resource_path.children_paths()?;
-
-        // for each dimension that a resource has been assessed, get the assessment
-        for assessed_dimension_path in assessed_dimensions_for_resource_typed_paths {
-            let assessments = get_links(assessed_dimension_path.path_entry_hash()?, LinkTypes::Assessment, None)?.into_iter().map(|link| {
-                let maybe_assessment = get_assessment(
-                  link.target.into_entry_hash()
-                    .ok_or(wasm_error!(WasmErrorInner::Guest(String::from("Invalid link target"))))?
-                )?;
-                if let Some(record) = maybe_assessment {
-                    let assessment = entry_from_record::<Assessment>(record)?;
-                    Ok(Some(assessment))
-                }
-                else {
-                    Ok(None)
-                }
-            }).collect::<ExternResult<Vec<Option<Assessment>>>>()?.into_iter().filter_map(|maybe_assessment| {
-                maybe_assessment
-            }).collect::<Vec<Assessment>>();
-            all_assessments.push(assessments);
+    // the assessments along each dimension that each resource has been assessed along, once each even if
+    // they are linked from both their shard and the legacy tree
+    let links = get_links_from_paths(assessed_dimension_paths()?, LinkTypes::Assessment)?;
+    get_linked_assessments(dedupe_by_target(links))
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetAssessmentsByAgentInput {
+    agent: AgentPubKey,
+    dimension_ehs: Option<Vec<EntryHash>>,
+    resource_def_ehs: Option<Vec<EntryHash>>,
+}
+
+#[hdk_extern]
+pub fn get_assessments_by_agent(
+    GetAssessmentsByAgentInput {
+        agent,
+        dimension_ehs,
+        resource_def_ehs,
+    }: GetAssessmentsByAgentInput,
+) -> ExternResult<Vec<Assessment>> {
+    let assessments = get_dimension_tagged_assessments(agent.clone(), LinkTypes::AgentToAssessment, &dimension_ehs)?
+        .into_iter()
+        // anyone can link to an agent, only the assessments they authored count
+        .filter(|assessment| assessment.author == agent && is_of_resource_defs(assessment, &resource_def_ehs))
+        .collect();
+    Ok(assessments)
+}
+
+// the assessments linked from `base` with links tagged by their dimension, only along `dimension_ehs` if given
+fn get_dimension_tagged_assessments(
+    base: impl Into<AnyLinkableHash> + Clone,
+    link_type: LinkTypes,
+    dimension_ehs: &Option<Vec<EntryHash>>,
+) -> ExternResult<Vec<Assessment>> {
+    let links = match dimension_ehs {
+        Some(dimension_ehs) => {
+            let link_type = link_type.try_into_filter()?;
+            let inputs = dimension_ehs
+                .iter()
+                .map(|dimension_eh| {
+                    GetLinksInput::new(
+                        base.clone().into(),
+                        link_type.clone(),
+                        Some(LinkTag::new(dimension_eh.to_string())),
+                    )
+                })
+                .collect();
+            get_links_batch(inputs)?.into_iter().flatten().collect()
         }
+        None => get_links(base, link_type, None)?,
+    };
+    get_linked_assessments(links)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetMyAssessmentsInput {
+    dimension_ehs: Option<Vec<EntryHash>>,
+    resource_def_ehs: Option<Vec<EntryHash>>,
+}
+
+#[hdk_extern]
+pub fn get_my_assessments(
+    GetMyAssessmentsInput {
+        dimension_ehs,
+        resource_def_ehs,
+    }: GetMyAssessmentsInput,
+) -> ExternResult<Vec<Assessment>> {
+    get_assessments_by_agent(GetAssessmentsByAgentInput {
+        agent: agent_info()?.agent_latest_pubkey,
+        dimension_ehs,
+        resource_def_ehs,
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetAssessmentsInTimeRangeInput {
+    resource_def_eh: EntryHash,
+    // inclusive
+    from: Timestamp,
+    // exclusive
+    to: Timestamp,
+    dimension_ehs: Option<Vec<EntryHash>>,
+}
+
+// reads only the hour buckets overlapping the window. Days that the window covers entirely are read
+// through their hours that have assessments, rather than every hour of the day
+#[hdk_extern]
+pub fn get_assessments_in_time_range(
+    GetAssessmentsInTimeRangeInput {
+        resource_def_eh,
+        from,
+        to,
+        dimension_ehs,
+    }: GetAssessmentsInTimeRangeInput,
+) -> ExternResult<Vec<Assessment>> {
+    if from > to {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "the time range must not end before it starts"
+        ))));
+    }
+    if from == to {
+        return Ok(vec![]);
     }
-    Ok(all_assessments.into_iter().flatten().collect())
+    if to.as_micros() - from.as_micros() > MAX_TIME_RANGE_DAYS * HOURS_PER_DAY * MICROS_PER_HOUR {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "the time range can span at most {} days",
+            MAX_TIME_RANGE_DAYS
+        ))));
+    }
+    let first_hour = assessment_hour(&from);
+    // `to` is exclusive, so the last hour is the one holding the microsecond before it
+    let last_hour = (to.as_micros() - 1).div_euclid(MICROS_PER_HOUR);
+
+    let mut bucket_paths = vec![];
+    let mut whole_days = vec![];
+    let mut hour = first_hour;
+    while hour <= last_hour {
+        let day = hour.div_euclid(HOURS_PER_DAY);
+        let day_start = day * HOURS_PER_DAY;
+        let day_end = day_start + HOURS_PER_DAY - 1;
+        if hour == day_start && day_end <= last_hour {
+            whole_days.push(day);
+            hour = day_end + 1;
+        } else {
+            bucket_paths.push(assessment_time_bucket_typed_path(resource_def_eh.clone(), hour)?);
+            hour += 1;
+        }
+    }
+    bucket_paths.extend(assessed_hour_paths(&resource_def_eh, whole_days)?);
+
+    let links = get_links_from_paths(bucket_paths, LinkTypes::AssessmentTimeBuckets)?;
+    let assessments = get_linked_assessments(links)?
+        .into_iter()
+        .filter(|assessment| {
+            // the first and last buckets may hold assessments outside of the window
+            let in_window = assessment.timestamp >= from && assessment.timestamp < to;
+            let in_dimensions = dimension_ehs
+                .as_ref()
+                .map_or(true, |dimension_ehs| dimension_ehs.contains(&assessment.dimension_eh));
+            in_window && in_dimensions
+        })
+        .collect();
+    Ok(assessments)
 }
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct UpdateAssessmentInput {
     original_action_hash: ActionHash,
@@ -167,20 +349,134 @@ pub fn delete_assessment(action_hash: ActionHash) -> ExternResult<ActionHash> {
     delete_entry(action_hash)
 }
 
+// `assessed_resources.<shard>.<resource>.<dimension>`, sharded by the resource
 pub fn assessment_typed_path(
     resource_eh: EntryHash,
     dimension_eh: EntryHash,
 ) -> ExternResult<TypedPath> {
-    let resource_eh_string = resource_eh.to_string();
-    let dimension_eh_string = dimension_eh.to_string();
+    let shard = shard_of(&resource_eh.clone().into());
     Ok(Path::from(format!(
-        "{}.{}.{}",
-        ALL_ASSESSED_RESOURCES_BASE, resource_eh_string, dimension_eh_string
+        "{}.{}.{}.{}",
+        ASSESSED_RESOURCES_BASE, shard, resource_eh, dimension_eh
     ))
     .typed(LinkTypes::Assessment)?)
 }
 
-pub fn all_assessments_typed_path() -> ExternResult<TypedPath> {
-    Ok(Path::from(ALL_ASSESSED_RESOURCES_BASE)
+// `all_assessed_resources.<resource>.<dimension>`, read until every assessment is migrated to its shard
+pub fn legacy_assessment_typed_path(
+    resource_eh: EntryHash,
+    dimension_eh: EntryHash,
+) -> ExternResult<TypedPath> {
+    Ok(Path::from(format!(
+        "{}.{}.{}",
+        LEGACY_ASSESSED_RESOURCES_BASE, resource_eh, dimension_eh
+    ))
     .typed(LinkTypes::Assessment)?)
 }
+
+// the `<resource>.<dimension>` paths assessments are linked from, across every shard and the legacy tree
+pub fn assessed_dimension_paths() -> ExternResult<Vec<TypedPath>> {
+    let mut resource_parents = vec![Path::from(LEGACY_ASSESSED_RESOURCES_BASE)];
+    resource_parents.extend(shard_paths(ASSESSED_RESOURCES_BASE));
+    let mut assessed_dimension_paths = vec![];
+    for resource_parent in resource_parents {
+        for assessed_resource_path in resource_parent.typed(LinkTypes::Assessment)?.children_paths()? {
+            assessed_dimension_paths.extend(assessed_resource_path.children_paths()?);
+        }
+    }
+    Ok(assessed_dimension_paths)
+}
+
+// moves the assessment links this agent made in the legacy tree to their shards, returning how many links were
+// made from the shards. An assessment already linked from its shard isn't linked again, so running this twice
+// doesn't duplicate links. Other agents' links stay in the legacy tree until they migrate, which reads keep scanning
+pub fn migrate_assessments_to_shards() -> ExternResult<usize> {
+    let me = agent_info()?.agent_latest_pubkey;
+    let legacy_root = Path::from(LEGACY_ASSESSED_RESOURCES_BASE).typed(LinkTypes::Assessment)?;
+    let mut assessed_dimension_paths = vec![];
+    for assessed_resource_path in legacy_root.children_paths()? {
+        assessed_dimension_paths.extend(assessed_resource_path.children_paths()?);
+    }
+    let links: Vec<Link> = get_links_from_paths(assessed_dimension_paths, LinkTypes::Assessment)?
+        .into_iter()
+        .filter(|link| link.author == me)
+        .collect();
+    let records = get_linked_records(links.clone())?;
+    let mut moves: Vec<(Link, TypedPath)> = vec![];
+    for (link, maybe_record) in links.into_iter().zip(records) {
+        // the path only holds the resource and dimension as strings, so they're read from the assessment
+        if let Some(record) = maybe_record {
+            let assessment = entry_from_record::<Assessment>(record)?;
+            moves.push((link, assessment_typed_path(assessment.resource_eh, assessment.dimension_eh)?));
+        }
+    }
+    let mut sharded_targets: BTreeSet<AnyLinkableHash> = get_links_from_paths(
+        moves.iter().map(|(_, assessment_path)| assessment_path.clone()).collect(),
+        LinkTypes::Assessment,
+    )?
+    .into_iter()
+    .map(|link| link.target)
+    .collect();
+    let mut moved = 0;
+    for (link, assessment_path) in moves {
+        delete_link(link.create_link_hash)?;
+        if sharded_targets.insert(link.target.clone()) {
+            assessment_path.ensure()?;
+            create_link(assessment_path.path_entry_hash()?, link.target, LinkTypes::Assessment, link.tag)?;
+            moved += 1;
+        }
+    }
+    Ok(moved)
+}
+
+// the assessments the links point to, fetched in a single call
+fn get_linked_assessments(links: Vec<Link>) -> ExternResult<Vec<Assessment>> {
+    get_linked_records(links)?
+        .into_iter()
+        .flatten()
+        .map(entry_from_record::<Assessment>)
+        .collect()
+}
+
+// hours since the unix epoch
+pub fn assessment_hour(timestamp: &Timestamp) -> i64 {
+    timestamp.as_micros().div_euclid(MICROS_PER_HOUR)
+}
+
+// `assessment_time_buckets.<resource def>.<day>.<hour>`, both counted since the unix epoch
+pub fn assessment_time_bucket_typed_path(resource_def_eh: EntryHash, hour: i64) -> ExternResult<TypedPath> {
+    Ok(Path::from(format!(
+        "{}.{}.{}.{}",
+        ASSESSMENT_TIME_BUCKETS_BASE,
+        resource_def_eh,
+        hour.div_euclid(HOURS_PER_DAY),
+        hour
+    ))
+    .typed(LinkTypes::AssessmentTimeBuckets)?)
+}
+
+// the hour buckets with assessments in each of the days, found from the links to them from the day paths,
+// which are all fetched in one call
+fn assessed_hour_paths(resource_def_eh: &EntryHash, days: Vec<i64>) -> ExternResult<Vec<TypedPath>> {
+    let link_type = LinkTypes::AssessmentTimeBuckets.try_into_filter()?;
+    let mut hour_paths: BTreeMap<AnyLinkableHash, TypedPath> = BTreeMap::new();
+    let mut inputs = vec![];
+    for day in days {
+        for hour in day * HOURS_PER_DAY..(day + 1) * HOURS_PER_DAY {
+            let hour_path = assessment_time_bucket_typed_path(resource_def_eh.clone(), hour)?;
+            hour_paths.insert(hour_path.path_entry_hash()?.into(), hour_path);
+        }
+        let day_path = assessment_time_bucket_day_typed_path(resource_def_eh.clone(), day)?;
+        inputs.push(GetLinksInput::new(day_path.path_entry_hash()?.into(), link_type.clone(), None));
+    }
+    Ok(get_links_batch(inputs)?
+        .into_iter()
+        .flatten()
+        .filter_map(|link| hour_paths.remove(&link.target))
+        .collect())
+}
+
+fn assessment_time_bucket_day_typed_path(resource_def_eh: EntryHash, day: i64) -> ExternResult<TypedPath> {
+    Ok(Path::from(format!("{}.{}.{}", ASSESSMENT_TIME_BUCKETS_BASE, resource_def_eh, day))
+        .typed(LinkTypes::AssessmentTimeBuckets)?)
+}
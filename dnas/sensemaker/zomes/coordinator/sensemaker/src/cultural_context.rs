@@ -3,15 +3,28 @@ use std::collections::BTreeMap;
 
 use hdk::prelude::*;
 use sensemaker_integrity::Assessment;
+use sensemaker_integrity::ContextResult;
 use sensemaker_integrity::CulturalContext;
 use sensemaker_integrity::EntryTypes;
+use sensemaker_integrity::LinkTypes;
 use sensemaker_integrity::OrderingKind;
+use sensemaker_integrity::RangeValue;
+use sensemaker_integrity::ResourceDef;
+use sensemaker_integrity::Role;
+use sensemaker_integrity::Threshold;
 
+use crate::agent_has_role;
+use crate::get_resource_def;
+use crate::refresh_role_checkpoint;
 use crate::utils::entry_from_record;
 use crate::utils::flatten_btree_map;
-use crate::utils::get_assessments_for_resource_inner;
+use crate::utils::get_assessments_for_resources_inner;
+use crate::utils::get_linked_records;
+use crate::utils::get_links_from_paths;
 use crate::utils::reduce_assessments_to_latest;
 
+const ALL_CULTURAL_CONTEXTS_BASE: &str = "all_cultural_contexts";
+
 #[hdk_extern]
 pub fn get_cultural_context(entry_hash: EntryHash) -> ExternResult<Option<Record>> {
     get(entry_hash, GetOptions::default())
@@ -19,9 +32,35 @@ pub fn get_cultural_context(entry_hash: EntryHash) -> ExternResult<Option<Record
 
 #[hdk_extern]
 pub fn create_cultural_context(cultural_context: CulturalContext) -> ExternResult<Record> {
+    refresh_role_checkpoint(())?;
     let action_hash = create_entry(&EntryTypes::CulturalContext(cultural_context.clone()))?;
+    let cultural_context_eh = hash_entry(&EntryTypes::CulturalContext(cultural_context.clone()))?;
     let record = get(action_hash.clone(), GetOptions::default())?;
     if let Some(record) = record {
+        // contexts are scoped to the applet of their resource def, so that names only need to be unique within an applet
+        let resource_def = match get_resource_def(cultural_context.resource_def_eh.clone())? {
+            Some(record) => entry_from_record::<ResourceDef>(record)?,
+            None => {
+                return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+                    "Resource Def Not Found"
+                ))))
+            }
+        };
+        let cultural_contexts_path = cultural_contexts_typed_path(resource_def.applet_eh)?;
+        // ensure the path components are created so we can fetch child paths later
+        cultural_contexts_path.ensure()?;
+        create_link(
+            cultural_contexts_path.path_entry_hash()?,
+            cultural_context_eh.clone(),
+            LinkTypes::CulturalContexts,
+            LinkTag::new(cultural_context.name.as_str()),
+        )?;
+        create_link(
+            cultural_context.resource_def_eh,
+            cultural_context_eh,
+            LinkTypes::ResourceDefToCulturalContext,
+            LinkTag::new(cultural_context.name.as_str()),
+        )?;
         Ok(record)
     } else {
         Err(wasm_error!(WasmErrorInner::Guest(String::from(
@@ -30,6 +69,52 @@ pub fn create_cultural_context(cultural_context: CulturalContext) -> ExternResul
     } 
 }
 
+#[hdk_extern]
+pub fn get_cultural_contexts(_: ()) -> ExternResult<Vec<Record>> {
+    let applet_paths = all_cultural_contexts_typed_path()?.children_paths()?;
+    let links = get_links_from_paths(applet_paths, LinkTypes::CulturalContexts)?;
+    get_cultural_contexts_from_links(links)
+}
+
+#[hdk_extern]
+pub fn get_cultural_contexts_for_resource_def(resource_def_eh: EntryHash) -> ExternResult<Vec<Record>> {
+    let links = get_links(resource_def_eh, LinkTypes::ResourceDefToCulturalContext, None)?;
+    get_cultural_contexts_from_links(links)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetCulturalContextByNameInput {
+    applet_eh: EntryHash,
+    name: String,
+}
+
+// when an applet has several contexts of the same name, the latest one is returned, then the one with the
+// lowest link hash, whatever order the links are returned in
+#[hdk_extern]
+pub fn get_cultural_context_by_name(input: GetCulturalContextByNameInput) -> ExternResult<Option<Record>> {
+    let link_tag = LinkTag::new(input.name.as_str());
+    // link tags are matched by prefix, so only keep the exact name
+    let mut links = get_links(
+        cultural_contexts_typed_path(input.applet_eh)?.path_entry_hash()?,
+        LinkTypes::CulturalContexts,
+        Some(link_tag.clone()),
+    )?
+    .into_iter()
+    .filter(|link| link.tag == link_tag)
+    .collect::<Vec<Link>>();
+    links.sort_by(|left, right| {
+        right
+            .timestamp
+            .cmp(&left.timestamp)
+            .then_with(|| left.create_link_hash.cmp(&right.create_link_hash))
+    });
+    Ok(get_cultural_contexts_from_links(links)?.into_iter().next())
+}
+
+fn get_cultural_contexts_from_links(links: Vec<Link>) -> ExternResult<Vec<Record>> {
+    Ok(get_linked_records(links)?.into_iter().flatten().collect())
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct UpdateCulturalContextInput {
     original_action_hash: ActionHash,
@@ -47,7 +132,7 @@ pub struct ComputeContextInput {
 pub fn compute_context(compute_context_input: ComputeContextInput) -> ExternResult<Vec<EntryHash>> {
     // get the context entry
     // for each entry hash, get the objective dimensions along which to check threshold
-    let maybe_record = get_cultural_context(compute_context_input.context_eh)?;
+    let maybe_record = get_cultural_context(compute_context_input.context_eh.clone())?;
     if let Some(record) = maybe_record {
         let cultural_context = entry_from_record::<CulturalContext>(record)?;
 
@@ -60,138 +145,195 @@ pub fn compute_context(compute_context_input: ComputeContextInput) -> ExternResu
             dimension_ehs.push(dimension_eh.clone());
             dimension_ordering_kind.insert(dimension_eh, ordering_kind);
         }
-        let mut all_resource_assessments: BTreeMap<
-            EntryHash,
-            BTreeMap<EntryHash, Vec<Assessment>>,
-        > = BTreeMap::new();
+        // thresholds can be on dimensions that aren't ordered by, so their assessments are needed too
+        let mut thresholds: Vec<&Threshold> = cultural_context.thresholds.iter().collect();
+        if let Some(condition) = &cultural_context.threshold_condition {
+            thresholds.extend(condition.thresholds());
+        }
+        for threshold in thresholds {
+            if !dimension_ehs.contains(&threshold.dimension_eh) {
+                dimension_ehs.push(threshold.dimension_eh.clone());
+            }
+        }
+        // the assessments of every resource are read up front, in two batched calls rather than one per resource
+        let mut all_resource_assessments = get_assessments_for_resources_inner(
+            compute_context_input.resource_ehs.clone(),
+            dimension_ehs,
+        )?;
         let mut unordered_context_result: Vec<(EntryHash, BTreeMap<EntryHash, Vec<Assessment>>)> =
             Vec::new();
         for resource_eh in compute_context_input.resource_ehs {
             // we should really only be using one assessment per dimension per resource, since these are objective dimensions
-            // for now going to just take the last one, but we will need to clarify exactly how to handle these situations
-            let resource_assessments =
-                get_assessments_for_resource_inner(resource_eh.clone(), dimension_ehs.clone())?;
-            all_resource_assessments.insert(resource_eh.clone(), resource_assessments.clone());
+            // for now going to just take the latest one, but we will need to clarify exactly how to handle these situations
+            let resource_assessments = match all_resource_assessments.remove(&resource_eh) {
+                Some(resource_assessments) => resource_assessments,
+                // the same resource was passed more than once
+                None => continue,
+            };
 
-            // check the assessments against all thresholds?
-            // flatten the assessment map and compare
-            let assessments = reduce_assessments_to_latest(flatten_btree_map(resource_assessments.clone()));
+            // each threshold is checked against the latest assessment on its own dimension
+            let latest_values: BTreeMap<EntryHash, RangeValue> =
+                reduce_assessments_to_latest(flatten_btree_map(resource_assessments.clone()))
+                    .into_iter()
+                    .map(|assessment| (assessment.dimension_eh, assessment.value))
+                    .collect();
 
-            // for each assessment, check against each threshold
-            // TODO: clarify the exact comparison logic between multiple assessments and thresholds
             let mut meets_threshold = true;
-            for assessment in assessments {
-                for threshold in cultural_context.thresholds.clone() {
-                    if !assessment.value.meets_threshold(threshold)? {
-                        meets_threshold = false;
-                    }
+            for threshold in cultural_context.thresholds.iter() {
+                if !threshold.is_met(&latest_values)? {
+                    meets_threshold = false;
+                }
+            }
+            if let Some(condition) = &cultural_context.threshold_condition {
+                if meets_threshold && !condition.is_met(&latest_values)? {
+                    meets_threshold = false;
                 }
             }
             if meets_threshold {
                 unordered_context_result.push((resource_eh, resource_assessments))
             }
         }
-        return Ok(order_resources(
-            unordered_context_result,
-            cultural_context.order_by,
-        )?);
-        // return Ok(context_result);
+        let ordered_context_result =
+            order_resources(unordered_context_result, cultural_context.order_by.clone())?;
+
+        if compute_context_input.can_publish_result {
+            refresh_role_checkpoint(())?;
+            // only a steward's result would pass validation, anyone else still gets the ordering
+            if agent_has_role(&Role::Steward)? {
+                publish_context_result(
+                    compute_context_input.context_eh,
+                    cultural_context,
+                    ordered_context_result.clone(),
+                )?;
+            } else {
+                warn!("not publishing the context result, only the community activator or a steward can publish one");
+            }
+        }
+        return Ok(ordered_context_result
+            .into_iter()
+            .map(|(resource_eh, _)| resource_eh)
+            .collect());
     } else {
         // TODO: better handling of this case
         return Ok(vec![]);
     }
 }
 
+// orders resources by each (dimension_eh, ordering_kind) pair in turn, later pairs only breaking ties
+// left by earlier ones. resources without an assessment on the first dimension are left out, missing
+// values on later dimensions sort last, and remaining ties are broken by resource hash so that every
+// agent renders the same order
 pub fn order_resources(
     unordered_context_result: Vec<(EntryHash, BTreeMap<EntryHash, Vec<Assessment>>)>,
     order_by: Vec<(EntryHash, OrderingKind)>,
-) -> ExternResult<Vec<EntryHash>> {
-    // get things into a format that can be ordered more easily
-    // for each dimension type, make an ordered list, return just the one
-    // want Vec<(EntryHash, Assessment)> for each dimension so then can order it based on the ordering kind
-    let mut ordered_by_dimension: BTreeMap<EntryHash, Vec<EntryHash>> = BTreeMap::new();
-    for (dimension_eh, ordering_kind) in order_by.clone() {
-        let mut unordered_for_dimension = Vec::new();
-        for (resource_eh, resource_assessments) in unordered_context_result.clone() {
-            let maybe_assessments = resource_assessments.get(&dimension_eh);
-            if let Some(assessments) = maybe_assessments {
-                let maybe_last_assessment = assessments.last();
-                if let Some(last_assessment) = maybe_last_assessment {
-                    unordered_for_dimension.push((resource_eh, last_assessment.clone()))
-                }
-            }
-        }
-        let ordered_for_dimension = order_by_dimension(unordered_for_dimension, ordering_kind)?;
-        ordered_by_dimension.insert(dimension_eh, ordered_for_dimension);
-    }
-    // for now we take the last (dimension_eh, ordering_kind) pair for simplicity
-    // in future will probably want to return something like `BTreeMap<DimensionEntryHash, Vec<ResourceEntryHash>>`
-    // so have an ordered list of resources for each dimension passed in
-
-    let maybe_dim_order_pair = order_by.last();
-    if let Some(dim_order_pair) = maybe_dim_order_pair {
-        let (dimension_eh, _) = dim_order_pair;
-        let maybe_ordered_on_dim = ordered_by_dimension.get(dimension_eh);
-        if let Some(ordered_on_dim) = maybe_ordered_on_dim {
-            Ok(ordered_on_dim.clone())
-        } else {
-            Ok(vec![])
+) -> ExternResult<Vec<(EntryHash, Vec<Option<RangeValue>>)>> {
+    let maybe_primary_dimension_eh = order_by.first().map(|(dimension_eh, _)| dimension_eh.clone());
+    let primary_dimension_eh = match maybe_primary_dimension_eh {
+        Some(dimension_eh) => dimension_eh,
+        // TODO: better error handling
+        None => return Ok(vec![]),
+    };
+
+    // the sort keys for each resource: the latest value along each ordering dimension
+    let mut keyed_resources: Vec<(EntryHash, Vec<Option<RangeValue>>)> = vec![];
+    for (resource_eh, resource_assessments) in unordered_context_result {
+        let latest_value = |dimension_eh: &EntryHash| {
+            resource_assessments
+                .get(dimension_eh)
+                .and_then(|assessments| assessments.iter().max_by_key(|assessment| assessment.timestamp))
+                .map(|assessment| assessment.value.clone())
+        };
+        if latest_value(&primary_dimension_eh).is_none() {
+            continue;
         }
+        let keys = order_by
+            .iter()
+            .map(|(dimension_eh, _)| latest_value(dimension_eh))
+            .collect();
+        keyed_resources.push((resource_eh, keys));
     }
-    // TODO: better error handling
-    else {
-        Ok(vec![])
-    }
-}
 
-pub fn order_by_dimension(
-    mut unordered_for_dimension: Vec<(EntryHash, Assessment)>,
-    ordering_kind: OrderingKind,
-) -> ExternResult<Vec<EntryHash>> {
-    let mut comparison_error = wasm_error!(WasmErrorInner::Guest(String::from("")));
-    let mut comparison_errored = false;
-    match ordering_kind {
-        OrderingKind::Biggest => {
-            unordered_for_dimension.sort_by(|(_, a_assessment), (_, b_assessment)| {
-                match b_assessment.value.compare(a_assessment.clone().value) {
-                    Ok(ordering) => ordering,
+    let mut comparison_error = None;
+    keyed_resources.sort_by(|(a_resource_eh, a_keys), (b_resource_eh, b_keys)| {
+        for ((a_value, b_value), (_, ordering_kind)) in a_keys.iter().zip(b_keys.iter()).zip(order_by.iter()) {
+            let ordering = match (a_value, b_value) {
+                (Some(a_value), Some(b_value)) => match a_value.compare(b_value.clone()) {
+                    Ok(ordering) => match ordering_kind {
+                        OrderingKind::Biggest => ordering.reverse(),
+                        OrderingKind::Smallest => ordering,
+                    },
                     Err(wasm_error) => {
-                        comparison_errored = true;
-                        comparison_error = wasm_error;
+                        comparison_error = Some(wasm_error);
                         Ordering::Equal
                     }
-                }
-            });
-            if comparison_errored {
-                Err(comparison_error)
-            } else {
-                Ok(unordered_for_dimension
-                    .into_iter()
-                    .map(|(resource_eh, _)| resource_eh)
-                    .collect())
+                },
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            };
+            if ordering != Ordering::Equal {
+                return ordering;
             }
         }
-        OrderingKind::Smallest => {
-            unordered_for_dimension.sort_by(|(_, a_assessment), (_, b_assessment)| {
-                match a_assessment.value.compare(b_assessment.clone().value) {
-                    Ok(ordering) => ordering,
-                    Err(wasm_error) => {
-                        comparison_errored = true;
-                        comparison_error = wasm_error;
-                        Ordering::Equal
-                    }
-                }
-            });
-            if comparison_errored {
-                Err(comparison_error)
-            } else {
-                Ok(unordered_for_dimension
-                    .into_iter()
-                    .map(|(resource_eh, _)| resource_eh)
-                    .collect())
+        a_resource_eh.cmp(b_resource_eh)
+    });
+    match comparison_error {
+        Some(wasm_error) => Err(wasm_error),
+        None => Ok(keyed_resources),
+    }
+}
+
+// commit the ordered resources with their values so that other agents can read the ranking without recomputing it
+fn publish_context_result(
+    context_eh: EntryHash,
+    cultural_context: CulturalContext,
+    ordered_context_result: Vec<(EntryHash, Vec<Option<RangeValue>>)>,
+) -> ExternResult<Record> {
+    let context_result = ContextResult {
+        context_eh: context_eh.clone(),
+        dimension_ehs: cultural_context
+            .order_by
+            .into_iter()
+            .map(|(dimension_eh, _)| dimension_eh)
+            .collect(),
+        result: ordered_context_result,
+    };
+    let action_hash = create_entry(&EntryTypes::ContextResult(context_result.clone()))?;
+    let context_result_eh = hash_entry(&EntryTypes::ContextResult(context_result))?;
+    let record = get(action_hash, GetOptions::default())?;
+    if let Some(record) = record {
+        create_link(
+            context_eh,
+            context_result_eh,
+            LinkTypes::CulturalContextToContextResult,
+            (),
+        )?;
+        Ok(record)
+    } else {
+        Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "not able to get context result record after create"
+        ))))
+    }
+}
+
+// the latest result published for the context. Only results computed for this context count, in case
+// a link from it points to the result of another one
+#[hdk_extern]
+pub fn get_latest_context_result(context_eh: EntryHash) -> ExternResult<Option<Record>> {
+    let mut links = get_links(context_eh.clone(), LinkTypes::CulturalContextToContextResult, None)?;
+    // results published at the same time are told apart by link hash so every agent picks the same one
+    links.sort_by(|a, b| (b.timestamp, &b.create_link_hash).cmp(&(a.timestamp, &a.create_link_hash)));
+    for link in links {
+        let context_result_eh = link.target.into_entry_hash().ok_or(wasm_error!(
+            WasmErrorInner::Guest(String::from("Invalid link target"))
+        ))?;
+        if let Some(record) = get(context_result_eh, GetOptions::default())? {
+            if entry_from_record::<ContextResult>(record.clone())?.context_eh == context_eh {
+                return Ok(Some(record));
             }
         }
     }
+    Ok(None)
 }
 
 #[hdk_extern]
@@ -203,3 +345,11 @@ pub fn update_cultural_context(input: UpdateCulturalContextInput) -> ExternResul
 pub fn delete_cultural_context(action_hash: ActionHash) -> ExternResult<ActionHash> {
     delete_entry(action_hash)
 }
+
+pub fn cultural_contexts_typed_path(applet_eh: EntryHash) -> ExternResult<TypedPath> {
+    Path::from(format!("{}.{}", ALL_CULTURAL_CONTEXTS_BASE, applet_eh)).typed(LinkTypes::CulturalContexts)
+}
+
+pub fn all_cultural_contexts_typed_path() -> ExternResult<TypedPath> {
+    Path::from(ALL_CULTURAL_CONTEXTS_BASE).typed(LinkTypes::CulturalContexts)
+}
@@ -1,20 +1,18 @@
 use hdk::prelude::*;
+use nh_sensemaker_zome_lib::sharding::{create_sharded_link, get_sharded_links};
 use sensemaker_integrity::{Dimension, EntryTypes, LinkTypes, PartialMethod, Method};
 
-use crate::create_method;
+use crate::{create_method, refresh_role_checkpoint};
+use crate::utils::get_linked_records;
 
 #[hdk_extern]
 pub fn create_dimension(dimension: Dimension) -> ExternResult<Record> {
+    refresh_role_checkpoint(())?;
     let action_hash = create_entry(&EntryTypes::Dimension(dimension.clone()))?;
     let dimension_eh = hash_entry(&EntryTypes::Dimension(dimension.clone()))?;
     let record = get(action_hash.clone(), GetOptions::default())?;
     if let Some(record) = record {
-        create_link(
-            dimensions_typed_path()?.path_entry_hash()?,
-            dimension_eh.clone(),
-            LinkTypes::Dimensions,
-            (),
-        )?;
+        create_sharded_link(DIMENSIONS_BASE, dimension_eh.clone(), LinkTypes::Dimensions, ())?;
         Ok(record)
     } else {
         Err(wasm_error!(WasmErrorInner::Guest(String::from(
@@ -79,16 +77,7 @@ pub struct AtomicDimensionMethodInput {
 
 #[hdk_extern]
 pub fn get_dimensions(_: ()) -> ExternResult<Vec<Option<Record>>> {
-    get_links(
-        dimensions_typed_path()?.path_entry_hash()?,
-        LinkTypes::Dimensions,
-        None,
-    )?
-    .into_iter()
-    .map(|link| get_dimension(link.target.into_entry_hash()
-        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from("Invalid link target"))))?
-    ))
-    .collect::<ExternResult<Vec<Option<Record>>>>()
+    get_linked_records(get_sharded_links(DIMENSIONS_BASE, LinkTypes::Dimensions, None)?)
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -110,6 +99,4 @@ pub struct UpdateDimensionInput {
 //     delete_entry(action_hash)
 // }
 
-pub fn dimensions_typed_path() -> ExternResult<TypedPath> {
-    Path::from("dimensions").typed(LinkTypes::Dimensions)
-}
+pub const DIMENSIONS_BASE: &str = "dimensions";
@@ -1,13 +1,14 @@
-use crate::{create_entries_from_applet_config, agent::all_agents_typed_path};
+use crate::{create_entries_from_applet_config, agent::ALL_AGENTS_BASE};
 use hdk::prelude::*;
+use nh_sensemaker_zome_lib::sharding::create_sharded_link;
 use sensemaker_integrity::{EntryTypes, LinkTypes, Properties};
 
 #[hdk_extern]
 pub fn init(_: ()) -> ExternResult<InitCallbackResult> {
     // register agent in NH
     // create a link from all agents path to my pub key
-    create_link(
-        all_agents_typed_path()?.path_entry_hash()?,
+    create_sharded_link(
+        ALL_AGENTS_BASE,
         agent_info()?.agent_latest_pubkey,
         LinkTypes::AllAgentsPath,
         (),
@@ -4,9 +4,12 @@ mod cultural_context;
 mod dimension;
 mod init;
 mod method;
+mod migration;
 mod range;
 mod resource_def;
+mod role;
 mod sensemaker_config;
+mod succession;
 mod utils;
 mod signals;
 mod agent;
@@ -16,6 +19,9 @@ pub use cultural_context::*;
 pub use dimension::*;
 pub use init::*;
 pub use method::*;
+pub use migration::*;
 pub use range::*;
 pub use resource_def::*;
-pub use sensemaker_config::*;
\ No newline at end of file
+pub use role::*;
+pub use sensemaker_config::*;
+pub use succession::*;
\ No newline at end of file
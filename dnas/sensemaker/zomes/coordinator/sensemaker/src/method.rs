@@ -1,18 +1,27 @@
 use std::collections::BTreeMap;
 
 use hdk::prelude::*;
+use nh_sensemaker_zome_lib::sharding::{create_sharded_link, get_sharded_links};
 use sensemaker_integrity::Assessment;
+use sensemaker_integrity::DataSet;
+use sensemaker_integrity::Dimension;
 use sensemaker_integrity::EntryTypes;
 use sensemaker_integrity::LinkTypes;
 use sensemaker_integrity::Method;
-use sensemaker_integrity::Program;
+use sensemaker_integrity::RangeKind;
 use sensemaker_integrity::RangeValue;
+use sensemaker_integrity::UnitEntryTypes;
 
 use crate::create_assessment;
 use crate::get_dimension;
+use crate::refresh_role_checkpoint;
+use crate::agent::get_all_agents;
+use crate::signals::Signal;
 use crate::utils::entry_from_record;
-use crate::utils::flatten_btree_map;
+use crate::utils::entry_hash_from_record;
 use crate::utils::get_assessments_for_resource_inner;
+use crate::utils::get_linked_records;
+use crate::utils::get_range_for_dimension;
 use crate::CreateAssessmentInput;
 
 #[hdk_extern]
@@ -22,34 +31,8 @@ pub fn get_method(entry_hash: EntryHash) -> ExternResult<Option<Record>> {
 
 #[hdk_extern]
 fn get_methods(_: ()) -> ExternResult<Vec<Record>> {
-    let links = get_links(
-        methods_typed_path()?.path_entry_hash()?,
-        LinkTypes::Method,
-        None,
-    )?;
-    match links.last() {
-        Some(_link) => {
-            let collected_get_results: ExternResult<Vec<Option<Record>>> = links
-                .into_iter()
-                .map(|link| {
-                    let entry_hash = link.target.into_entry_hash().ok_or_else(|| {
-                        wasm_error!(WasmErrorInner::Guest(String::from("Invalid link target")))
-                    })?;
-
-                    get_method(entry_hash)
-                })
-                .collect();
-
-            // Handle the Result and then filter_map to remove None values
-            collected_get_results.map(|maybe_records| {
-                maybe_records
-                    .into_iter()
-                    .filter_map(|maybe_record| maybe_record)
-                    .collect::<Vec<Record>>()
-            })
-        }
-        None => Ok(vec![]),
-    }
+    let links = get_sharded_links(METHODS_BASE, LinkTypes::Method, None)?;
+    Ok(get_linked_records(links)?.into_iter().flatten().collect())
 }
 
 #[derive(Serialize, Deserialize, Debug, SerializedBytes, Clone)]
@@ -83,17 +66,7 @@ pub fn get_methods_for_dimension(input: GetMethodsForDimensionInput) -> ExternRe
                         Some(link_tag),
                     )?;
             
-                    Ok(links.iter()
-                        .map(|link| {
-                            get_method(link.target.clone().into_entry_hash()
-                                .ok_or(wasm_error!(WasmErrorInner::Guest(String::from("Invalid link target"))))?
-                            )
-                        })
-                        .collect::<ExternResult<Vec<Option<Record>>>>()?.into_iter().filter_map(|maybe_method| {
-                            maybe_method
-                        })
-                        .collect::<Vec<Record>>()
-                    )
+                    Ok(get_linked_records(links)?.into_iter().flatten().collect())
                 }
                 _ => Err(wasm_error!(WasmErrorInner::Guest(String::from(
                     "invalid query parameter dimension_type"
@@ -112,12 +85,14 @@ pub fn get_methods_for_dimension(input: GetMethodsForDimensionInput) -> ExternRe
 
 #[hdk_extern]
 pub fn create_method(method: Method) -> ExternResult<Record> {
+    check_program(&method)?;
+    refresh_role_checkpoint(())?;
     let action_hash = create_entry(&EntryTypes::Method(method.clone()))?;
     let method_eh = hash_entry(&EntryTypes::Method(method.clone()))?;
     let record = get(action_hash.clone(), GetOptions::default())?;
     if let Some(record) = record {
-        create_link(
-            methods_typed_path()?.path_entry_hash()?,
+        create_sharded_link(
+            METHODS_BASE,
             method_eh.clone(),
             LinkTypes::Method,
             LinkTag::new("input"),
@@ -146,6 +121,25 @@ pub fn create_method(method: Method) -> ExternResult<Record> {
     }
 }
 
+// reject programs that can't run on the input dimensions' ranges or don't produce values in the output dimension's range
+fn check_program(method: &Method) -> ExternResult<()> {
+    let input_range_kinds = method
+        .input_dimension_ehs
+        .iter()
+        .map(|dimension_eh| Ok(get_range_for_dimension(dimension_eh.clone())?.kind))
+        .collect::<ExternResult<Vec<RangeKind>>>()?;
+    let output_range_kind = get_range_for_dimension(method.output_dimension_eh.clone())?.kind;
+    method
+        .program
+        .type_check(&input_range_kinds, &output_range_kind)
+        .map_err(|error| {
+            wasm_error!(WasmErrorInner::Guest(format!(
+                "method {} has an invalid program: {}",
+                method.name, error
+            )))
+        })
+}
+
 #[hdk_extern]
 pub fn run_method(input: RunMethodInput) -> ExternResult<Option<Record>> {
     let maybe_record = get_method(input.method_eh.clone())?;
@@ -160,10 +154,10 @@ pub fn run_method(input: RunMethodInput) -> ExternResult<Option<Record>> {
         )?;
         // now have all assessments with the associated dimension hash
         // stored as a BTreeMap in case its important to know which dimension the assessment is on
-        // now check what program it is, and depending on the range value type do math accordingly
-        // if doing multiple input dimensions, will want to make sure they are of compatible types for arithmetic.
+        // the program was type checked against the input dimensions' ranges when the method was created
 
         let maybe_objective_assessment = compute_objective_assessment(
+            input.method_eh,
             method,
             assessments,
             input.resource_eh,
@@ -184,81 +178,150 @@ pub fn run_method(input: RunMethodInput) -> ExternResult<Option<Record>> {
     }
 }
 
+// recompute live methods whenever a subjective assessment on one of their input dimensions is committed
+#[hdk_extern(infallible)]
+pub fn post_commit(committed_actions: Vec<SignedActionHashed>) {
+    for action in committed_actions {
+        if let Err(err) = recompute_live_methods(action) {
+            error!("Error recomputing live methods: {:?}", err);
+        }
+    }
+}
+
+fn recompute_live_methods(action: SignedActionHashed) -> ExternResult<()> {
+    let assessment_entry_type: EntryType = UnitEntryTypes::Assessment.try_into()?;
+    match action.hashed.content.clone() {
+        Action::Create(create) if create.entry_type == assessment_entry_type => {}
+        _ => return Ok(()),
+    }
+    let assessment = match get(action.hashed.hash, GetOptions::default())? {
+        Some(record) => entry_from_record::<Assessment>(record)?,
+        None => return Ok(()),
+    };
+    // objective assessments are the output of methods, recomputing on them could loop forever
+    let dimension = match get_dimension(assessment.dimension_eh.clone())? {
+        Some(record) => entry_from_record::<Dimension>(record)?,
+        None => return Ok(()),
+    };
+    if dimension.computed {
+        return Ok(());
+    }
+
+    let links = get_links(
+        assessment.dimension_eh.clone(),
+        LinkTypes::DimensionToMethod,
+        Some(LinkTag::new("input")),
+    )?;
+    for record in get_linked_records(links)?.into_iter().flatten() {
+        // a failing method mustn't keep the others from running
+        if let Err(err) = run_live_method(&assessment, record) {
+            error!("Error running live method: {:?}", err);
+        }
+    }
+    Ok(())
+}
+
+// runs a live method on the resource of the assessment and signals its result to every agent
+fn run_live_method(assessment: &Assessment, method_record: Record) -> ExternResult<()> {
+    let method_eh = entry_hash_from_record(method_record.clone())?;
+    let method = entry_from_record::<Method>(method_record)?;
+    if !method.can_compute_live {
+        return Ok(());
+    }
+    // post_commit can't write to the source chain, so the method is run in its own zome call
+    let response = call(
+        CallTargetCell::Local,
+        zome_info()?.name,
+        "run_method".into(),
+        None,
+        RunMethodInput {
+            resource_eh: assessment.resource_eh.clone(),
+            resource_def_eh: assessment.resource_def_eh.clone(),
+            method_eh: method_eh.clone(),
+        },
+    )?;
+    let maybe_record: Option<Record> = match response {
+        ZomeCallResponse::Ok(result) => result
+            .decode()
+            .map_err(|err| wasm_error!(WasmErrorInner::Guest(err.into())))?,
+        other => {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "not able to run live method {}: {:?}",
+                method.name, other
+            ))))
+        }
+    };
+    if let Some(record) = maybe_record {
+        let signal = Signal::LiveMethodResult {
+            method_eh,
+            assessment: entry_from_record::<Assessment>(record)?,
+        };
+        let encoded_signal = ExternIO::encode(signal)
+            .map_err(|err| wasm_error!(WasmErrorInner::Guest(err.into())))?;
+        remote_signal(encoded_signal, get_all_agents(())?)?;
+    }
+    Ok(())
+}
+
 fn compute_objective_assessment(
+    method_eh: EntryHash,
     method: Method,
     assessments: BTreeMap<EntryHash, Vec<Assessment>>,
     resource_eh: EntryHash,
     resource_def_eh: EntryHash,
 ) -> ExternResult<Option<CreateAssessmentInput>> {
-    //
-    match method.program {
-        Program::Sum => {
-            // collapse into vec for easy computation - will have to be more careful if of different types
-            let mut is_int: bool = true;
-            let flat_assessments = flatten_btree_map(assessments);
-            let mut sum: u32 = 0;
-            let mut sum_float: f64 = 0.0;
-            for assessment in flat_assessments {
-                match assessment.value {
-                    RangeValue::Integer(value) => {
-                        sum = sum + value;
-                    }
-                    RangeValue::Float(value) => {
-                        sum_float = sum_float + value;
-                        is_int = false;
-                    }
-                }
-            }
-            let assessment_value: RangeValue;
-            if is_int {
-                assessment_value = RangeValue::Integer(sum);
-            } else {
-                assessment_value = RangeValue::Float(sum_float);
-            }
-            let assessment = CreateAssessmentInput {
-                value: assessment_value,
-                dimension_eh: method.output_dimension_eh,
-                resource_eh,
-                resource_def_eh,
-                maybe_input_dataset: None,
-            };
-            Ok(Some(assessment))
-        }
-        Program::Average => {
-            // collapse into vec for easy computation - will have to be more careful if of different types
-            let mut is_int: bool = true;
-            let flat_assessments = flatten_btree_map(assessments);
-            let mut sum: u32 = 0;
-            let mut sum_float: f64 = 0.0;
-
-            for assessment in flat_assessments.clone() {
-                match assessment.value {
-                    RangeValue::Integer(value) => sum = sum + value,
-                    RangeValue::Float(value) => {
-                        sum_float = sum_float + value;
-                        is_int = false;
-                    } // TODO: complete this
-                }
-            }
-            let assessment_value: RangeValue;
-
-            if is_int {
-                let average = sum / flat_assessments.len() as u32;
-                assessment_value = RangeValue::Integer(average);
-            } else {
-                let average = sum_float / flat_assessments.len() as f64;
-                assessment_value = RangeValue::Float(average);
-            }
-            let assessment = CreateAssessmentInput {
-                value: assessment_value,
-                dimension_eh: method.output_dimension_eh,
-                resource_eh,
-                resource_def_eh,
-                maybe_input_dataset: None,
-            };
-            Ok(Some(assessment))
-        }
-    }
+    // keep the values grouped per input dimension, in the order the method declares them,
+    // so that expressions can refer to a specific input dimension
+    let inputs = method
+        .input_dimension_ehs
+        .iter()
+        .map(|dimension_eh| {
+            assessments
+                .get(dimension_eh)
+                .map(|dimension_assessments| {
+                    dimension_assessments
+                        .iter()
+                        .map(|assessment| assessment.value.clone())
+                        .collect::<Vec<RangeValue>>()
+                })
+                .unwrap_or_default()
+        })
+        .collect::<Vec<Vec<RangeValue>>>();
+    let output_range_kind = get_range_for_dimension(method.output_dimension_eh.clone())?.kind;
+    let value = method.program.compute(inputs, &output_range_kind)?;
+    // the dataset lets validators recompute the value from exactly the assessments that were used
+    let maybe_input_dataset = if method.requires_validation {
+        let data_points = method
+            .input_dimension_ehs
+            .iter()
+            .map(|dimension_eh| {
+                let assessment_ehs = assessments
+                    .get(dimension_eh)
+                    .cloned()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|assessment| hash_entry(&EntryTypes::Assessment(assessment)))
+                    .collect::<ExternResult<Vec<EntryHash>>>()?;
+                Ok((dimension_eh.clone(), assessment_ehs))
+            })
+            .collect::<ExternResult<Vec<(EntryHash, Vec<EntryHash>)>>>()?;
+        let dataset = DataSet {
+            from: method_eh,
+            data_points,
+        };
+        create_entry(&EntryTypes::DataSet(dataset.clone()))?;
+        Some(dataset)
+    } else {
+        None
+    };
+    let assessment = CreateAssessmentInput {
+        value,
+        dimension_eh: method.output_dimension_eh,
+        resource_eh,
+        resource_def_eh,
+        maybe_input_dataset,
+    };
+    Ok(Some(assessment))
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -275,6 +338,7 @@ pub struct UpdateMethodInput {
 
 #[hdk_extern]
 pub fn update_method(input: UpdateMethodInput) -> ExternResult<ActionHash> {
+    check_program(&input.updated_method)?;
     update_entry(input.original_action_hash, &input.updated_method)
 }
 
@@ -283,6 +347,4 @@ pub fn delete_method(action_hash: ActionHash) -> ExternResult<ActionHash> {
     delete_entry(action_hash)
 }
 
-pub fn methods_typed_path() -> ExternResult<TypedPath> {
-    Path::from("methods").typed(LinkTypes::Method)
-}
+pub const METHODS_BASE: &str = "methods";
@@ -0,0 +1,22 @@
+use hdk::prelude::*;
+use nh_sensemaker_zome_lib::sharding::migrate_to_shards;
+use sensemaker_integrity::LinkTypes;
+
+use crate::{
+    agent::ALL_AGENTS_BASE, migrate_assessments_to_shards, DIMENSIONS_BASE, METHODS_BASE,
+    RANGES_BASE, RESOURCE_DEFS_BASE,
+};
+
+// moves the links this agent made from the single anchors used before sharding to their shards, returning how
+// many links were made from the shards. Listing reads the legacy anchors as well, as the links of agents who
+// haven't migrated stay there
+#[hdk_extern]
+pub fn migrate_sharded_anchors(_: ()) -> ExternResult<u32> {
+    let moved = migrate_to_shards(RESOURCE_DEFS_BASE, LinkTypes::ResourceDefs)?
+        + migrate_to_shards(RANGES_BASE, LinkTypes::Ranges)?
+        + migrate_to_shards(DIMENSIONS_BASE, LinkTypes::Dimensions)?
+        + migrate_to_shards(METHODS_BASE, LinkTypes::Method)?
+        + migrate_to_shards(ALL_AGENTS_BASE, LinkTypes::AllAgentsPath)?
+        + migrate_assessments_to_shards()?;
+    Ok(moved as u32)
+}
@@ -1,19 +1,18 @@
 use hdk::prelude::*;
+use nh_sensemaker_zome_lib::sharding::{create_sharded_link, get_sharded_links};
 use sensemaker_integrity::{EntryTypes, LinkTypes, Range};
 
+use crate::{refresh_role_checkpoint, utils::get_linked_records};
+
 #[hdk_extern]
 pub fn create_range(range: Range) -> ExternResult<Record> {
+    refresh_role_checkpoint(())?;
     let action_hash = create_entry(&EntryTypes::Range(range.clone()))?;
     let range_eh = hash_entry(&EntryTypes::Range(range.clone()))?;
 
     let record = get(action_hash.clone(), GetOptions::default())?;
     if let Some(record) = record {
-        create_link(
-            ranges_typed_path()?.path_entry_hash()?,
-            range_eh.clone(),
-            LinkTypes::Ranges,
-            (),
-        )?;
+        create_sharded_link(RANGES_BASE, range_eh.clone(), LinkTypes::Ranges, ())?;
         Ok(record)
     } else {
         Err(wasm_error!(WasmErrorInner::Guest(String::from(
@@ -29,19 +28,7 @@ pub fn get_range(entry_hash: EntryHash) -> ExternResult<Option<Record>> {
 
 #[hdk_extern]
 pub fn get_ranges(_: ()) -> ExternResult<Vec<Option<Record>>> {
-    get_links(
-        ranges_typed_path()?.path_entry_hash()?,
-        LinkTypes::Ranges,
-        None,
-    )?
-    .into_iter()
-    .map(|link| get_range(link.target.into_entry_hash()
-      .ok_or(wasm_error!(WasmErrorInner::Guest(String::from("Invalid link target"))))?
-    ))
-    .collect::<ExternResult<Vec<Option<Record>>>>()
+    get_linked_records(get_sharded_links(RANGES_BASE, LinkTypes::Ranges, None)?)
 }
 
-// TODO: revisit for potential hotspot
-fn ranges_typed_path() -> ExternResult<TypedPath> {
-    Path::from("ranges").typed(LinkTypes::Dimensions)
-}
+pub const RANGES_BASE: &str = "ranges";
@@ -1,8 +1,11 @@
 use hdk::prelude::*;
+use nh_sensemaker_zome_lib::sharding::{create_sharded_link, get_sharded_links};
 use sensemaker_integrity::EntryTypes;
 use sensemaker_integrity::LinkTypes;
 use sensemaker_integrity::ResourceDef;
 
+use crate::{refresh_role_checkpoint, utils::get_linked_records};
+
 #[hdk_extern]
 pub fn get_resource_def(entry_hash: EntryHash) -> ExternResult<Option<Record>> {
     get(entry_hash, GetOptions::default())
@@ -10,38 +13,13 @@ pub fn get_resource_def(entry_hash: EntryHash) -> ExternResult<Option<Record>> {
 
 #[hdk_extern]
 fn get_resource_defs(_: ()) -> ExternResult<Vec<Record>> {
-    let links = get_links(
-        resource_defs_typed_path()?.path_entry_hash()?,
-        LinkTypes::ResourceDefs,
-        None,
-    )?;
-    match links.last() {
-        Some(_link) => {
-            let collected_get_results: ExternResult<Vec<Option<Record>>> = links
-                .into_iter()
-                .map(|link| {
-                    let entry_hash = link.target.into_entry_hash().ok_or_else(|| {
-                        wasm_error!(WasmErrorInner::Guest(String::from("Invalid link target")))
-                    })?;
-
-                    get_resource_def(entry_hash)
-                })
-                .collect();
-
-            // Handle the Result and then filter_map to remove None values
-            collected_get_results.map(|maybe_records| {
-                maybe_records
-                    .into_iter()
-                    .filter_map(|maybe_record| maybe_record)
-                    .collect::<Vec<Record>>()
-            })
-        }
-        None => Ok(vec![]),
-    }
+    let links = get_sharded_links(RESOURCE_DEFS_BASE, LinkTypes::ResourceDefs, None)?;
+    Ok(get_linked_records(links)?.into_iter().flatten().collect())
 }
 
 #[hdk_extern]
 pub fn create_resource_def(resource_def: ResourceDef) -> ExternResult<Record> {
+    refresh_role_checkpoint(())?;
     let entry = EntryTypes::ResourceDef(resource_def.clone());
     let action_hash = create_entry(&entry)?;
     let resource_def_eh = hash_entry(&entry)?;
@@ -49,8 +27,8 @@ pub fn create_resource_def(resource_def: ResourceDef) -> ExternResult<Record> {
     let record = get(action_hash.clone(), GetOptions::default())?;
 
     if let Some(record) = record {
-        create_link(
-            resource_defs_typed_path()?.path_entry_hash()?,
+        create_sharded_link(
+            RESOURCE_DEFS_BASE,
             resource_def_eh.clone(),
             LinkTypes::ResourceDefs,
             (),
@@ -79,7 +57,4 @@ pub fn delete_resource_def(action_hash: ActionHash) -> ExternResult<ActionHash>
     delete_entry(action_hash)
 }
 
-pub fn resource_defs_typed_path() -> ExternResult<TypedPath> {
-    // TODO: break up path to prevent hotspots
-    Path::from("resource_defs").typed(LinkTypes::ResourceDefs)
-}
+pub const RESOURCE_DEFS_BASE: &str = "resource_defs";
@@ -0,0 +1,145 @@
+use std::collections::BTreeSet;
+
+use hdk::prelude::*;
+use sensemaker_integrity::{
+    has_unrevoked_role, EntryTypes, LinkTypes, Role, RoleCheckpoint, RoleClaim, RoleGrant, RoleRevocation,
+    UnitEntryTypes,
+};
+
+use crate::{
+    succession::resolve_community_activator,
+    utils::{agent_chain_head, entry_from_record, get_records},
+};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GrantRoleInput {
+    grantee: AgentPubKey,
+    role: Role,
+}
+
+// only the community activator can grant roles, the grantee then has to claim it with `claim_role`
+#[hdk_extern]
+pub fn grant_role(input: GrantRoleInput) -> ExternResult<Record> {
+    let grant = RoleGrant {
+        grantee: input.grantee.clone(),
+        role: input.role,
+    };
+    let action_hash = create_entry(&EntryTypes::RoleGrant(grant))?;
+    create_link(input.grantee, action_hash.clone(), LinkTypes::AgentToRoleGrant, ())?;
+    get(action_hash, GetOptions::default())?.ok_or(wasm_error!(WasmErrorInner::Guest(
+        String::from("not able to get role grant record after create")
+    )))
+}
+
+// commits a claim on the grantee's source chain, with a checkpoint of the community activator's chain, the role is
+// held from the next action on
+#[hdk_extern]
+pub fn claim_role(grant_ah: ActionHash) -> ExternResult<Record> {
+    if revoked_grant_ahs(agent_info()?.agent_latest_pubkey)?.contains(&grant_ah) {
+        return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "the role grant has been revoked"
+        ))));
+    }
+    let action_hash = create_entry(&EntryTypes::RoleClaim(RoleClaim { grant_ah }))?;
+    refresh_role_checkpoint(())?;
+    get(action_hash, GetOptions::default())?.ok_or(wasm_error!(WasmErrorInner::Guest(
+        String::from("not able to get role claim record after create")
+    )))
+}
+
+// only the community activator can revoke roles. The grantee no longer holds the role from the grant once
+// they checkpoint the community activator's chain past the revocation, which their next role-gated write does,
+// or once their checkpoint from before it lapses
+#[hdk_extern]
+pub fn revoke_role(grant_ah: ActionHash) -> ExternResult<Record> {
+    let grant_record = get(grant_ah.clone(), GetOptions::default())?.ok_or(wasm_error!(
+        WasmErrorInner::Guest(String::from("role grant not found"))
+    ))?;
+    let grant = entry_from_record::<RoleGrant>(grant_record)?;
+    let action_hash = create_entry(&EntryTypes::RoleRevocation(RoleRevocation { grant_ah }))?;
+    create_link(grant.grantee, action_hash.clone(), LinkTypes::AgentToRoleRevocation, ())?;
+    get(action_hash, GetOptions::default())?.ok_or(wasm_error!(WasmErrorInner::Guest(
+        String::from("not able to get role revocation record after create")
+    )))
+}
+
+// the grants made to the agent that haven't been revoked
+#[hdk_extern]
+pub fn get_role_grants_for_agent(agent: AgentPubKey) -> ExternResult<Vec<Record>> {
+    let revoked_grant_ahs = revoked_grant_ahs(agent.clone())?;
+    let links = get_links(agent, LinkTypes::AgentToRoleGrant, None)?;
+    let grant_ahs = links
+        .into_iter()
+        .map(|link| {
+            link.target.into_action_hash().ok_or(wasm_error!(WasmErrorInner::Guest(
+                String::from("Invalid link target")
+            )))
+        })
+        .collect::<ExternResult<Vec<ActionHash>>>()?
+        .into_iter()
+        .filter(|grant_ah| !revoked_grant_ahs.contains(grant_ah))
+        .collect();
+    Ok(get_records(grant_ahs)?.into_iter().flatten().collect())
+}
+
+#[hdk_extern]
+pub fn i_have_role(role: Role) -> ExternResult<bool> {
+    agent_has_role(&role)
+}
+
+pub fn agent_has_role(role: &Role) -> ExternResult<bool> {
+    let agent_info = agent_info()?;
+    has_unrevoked_role(
+        &agent_info.agent_latest_pubkey,
+        role,
+        Some(agent_info.chain_head.0),
+        sys_time()?,
+        &revoked_grant_ahs(agent_info.agent_latest_pubkey.clone())?,
+    )
+}
+
+// checkpoints the community activator's chain as far as this agent can see it, so that validation reads the
+// revocations up to there. Called before every write that needs a role, and does nothing for an agent without
+// role claims or whose latest checkpoint already pins the community activator's chain head
+#[hdk_extern]
+pub fn refresh_role_checkpoint(_: ()) -> ExternResult<()> {
+    if query_entries(UnitEntryTypes::RoleClaim)?.is_empty() {
+        return Ok(());
+    }
+    let (community_activator, _) = resolve_community_activator()?;
+    let chain_top = agent_chain_head(community_activator)?;
+    let latest_checkpoint = match query_entries(UnitEntryTypes::RoleCheckpoint)?.pop() {
+        Some(record) => Some(entry_from_record::<RoleCheckpoint>(record)?),
+        None => None,
+    };
+    if let Some(checkpoint) = latest_checkpoint {
+        if checkpoint.community_activator_chain_top == chain_top {
+            return Ok(());
+        }
+    }
+    create_entry(&EntryTypes::RoleCheckpoint(RoleCheckpoint {
+        community_activator_chain_top: chain_top,
+    }))?;
+    Ok(())
+}
+
+// revocations are linked from the grantee. Only the community activator can commit one, so every
+// revocation linked counts, and anything else linked is skipped
+fn revoked_grant_ahs(agent: AgentPubKey) -> ExternResult<BTreeSet<ActionHash>> {
+    let revocation_ahs = get_links(agent, LinkTypes::AgentToRoleRevocation, None)?
+        .into_iter()
+        .filter_map(|link| link.target.into_action_hash())
+        .collect::<Vec<ActionHash>>();
+    Ok(get_records(revocation_ahs)?
+        .into_iter()
+        .flatten()
+        .filter_map(|record| entry_from_record::<RoleRevocation>(record).ok())
+        .map(|revocation| revocation.grant_ah)
+        .collect())
+}
+
+// this agent's records of the entry type, oldest first
+fn query_entries(entry_type: UnitEntryTypes) -> ExternResult<Vec<Record>> {
+    let entry_type: EntryType = entry_type.try_into()?;
+    query(ChainQueryFilter::new().entry_type(entry_type).include_entries(true))
+}
@@ -1,70 +1,38 @@
 use hdk::prelude::*;
 use sensemaker_integrity::{LinkTypes, Properties, SensemakerConfig};
 
+use crate::{community_activator_succession, utils::entry_from_record};
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct UpdateConfigurationInput {
     original_action_hash: ActionHash, // this must be the original Create Action Hash
     updated_sensemaker_config: SensemakerConfig,
 }
 
+// the latest update to the original config written by the current community activator, or by the community
+// activator before them if they haven't written one yet, down to the original config
 #[hdk_extern]
 pub fn get_latest_sensemaker_config(_: ()) -> ExternResult<Option<Record>> {
-    let ca_key: AgentPubKey = Properties::get()?
-        .sensemaker_config
-        .community_activator
-        .into();
-
-    // get details of the first Sensemaker entry created in order to get all updates to it.
-    let links = get_links(ca_key, LinkTypes::CAToSensemakerConfig, None)?;
-
-    // return None if links is empty
-    if links.is_empty() {
-        return Ok(None);
-    }
-
-    // get the SM config eh from first link assuming that there is only one sensemaker config created, since subsequent ones are all udpates.
-    // can unwrap since links is not empty
-    let original_config_eh: EntryHash = links.get(0).unwrap().target.clone().into_entry_hash().ok_or(wasm_error!(WasmErrorInner::Guest(String::from("Invalid link target"))))?;
-    // get detail and immediately return None if None
-    let detail = get_details(original_config_eh.clone(), GetOptions::default())?;
-
-    if detail.is_none() {
-        return Ok(None);
-    }
-
-    // match the detail arm. since eh was given, Entry arm is the place to do the operation.
-    // we can get all updates to the entry and compare their timestamp and retrieve the latest updates visible to the agent.
-    // can unwrap since is_none is called above
-    match detail.unwrap() {
-        Details::Entry(entry_detail) => {
-            let maybe_update = entry_detail.updates.into_iter().max_by(|a, b| {
-                let a_ts = a.action().timestamp();
-                let b_ts = b.action().timestamp();
-                a_ts.cmp(&b_ts)
-            });
-            if let Some(update) = maybe_update {
-                // match update and return unreachable macro for all arms except the Update arm of Action
-                match update.action() {
-                    Action::Update(update) => {
-                        // get the entry hash from the update
-                        let updated_entry_hash: EntryHash = update.entry_hash.clone();
-
-                        // get the entry from the entry hash
-                        if let Some(record) = get(updated_entry_hash, GetOptions::default())? {
-                            Ok(Some(record))
-                        } else {
-                            Ok(None)
-                        }
-                    }
-                    _ => unreachable!(),
-                }
-            } else {
-                // return the original sensemakerConfig if there is no update visible
-                get(original_config_eh, GetOptions::default())
-            }
+    let original_config_eh = match original_config_eh()? {
+        Some(original_config_eh) => original_config_eh,
+        None => return Ok(None),
+    };
+    let entry_detail = match get_details(original_config_eh.clone(), GetOptions::default())? {
+        Some(Details::Entry(entry_detail)) => entry_detail,
+        _ => return Ok(None),
+    };
+    // the updates of one agent are ordered by their chain, unlike the timestamps of different agents
+    for (community_activator, _) in community_activator_succession()?.into_iter().rev() {
+        let latest_update = entry_detail
+            .updates
+            .iter()
+            .filter(|update| *update.action().author() == community_activator)
+            .max_by_key(|update| update.action().action_seq());
+        if let Some(Action::Update(update)) = latest_update.map(|update| update.action()) {
+            return get(update.entry_hash.clone(), GetOptions::default());
         }
-        Details::Record(_) => unreachable!(), // this arm is unreachable given that we provided entry hash
     }
+    get(original_config_eh, GetOptions::default())
 }
 
 #[hdk_extern]
@@ -75,3 +43,41 @@ pub fn update_sensemaker_config(config: UpdateConfigurationInput) -> ExternResul
         config.updated_sensemaker_config.clone(),
     )
 }
+
+// names a new community activator in the latest sensemaker config, as an update of the original config entry
+pub fn set_community_activator(community_activator: AgentPubKey) -> ExternResult<Option<ActionHash>> {
+    let original_config_eh = match original_config_eh()? {
+        Some(original_config_eh) => original_config_eh,
+        None => return Ok(None),
+    };
+    let original_action_hash = match get(original_config_eh, GetOptions::default())? {
+        Some(record) => record.action_address().clone(),
+        None => return Ok(None),
+    };
+    let latest_config = match get_latest_sensemaker_config(())? {
+        Some(record) => entry_from_record::<SensemakerConfig>(record)?,
+        None => return Ok(None),
+    };
+    let updated_sensemaker_config = SensemakerConfig {
+        community_activator: community_activator.into(),
+        ..latest_config
+    };
+    Ok(Some(update_entry(original_action_hash, updated_sensemaker_config)?))
+}
+
+// the config created by the community activator in the DNA properties, which every update updates
+fn original_config_eh() -> ExternResult<Option<EntryHash>> {
+    let ca_key: AgentPubKey = Properties::get()?
+        .sensemaker_config
+        .community_activator
+        .into();
+    let links = get_links(ca_key, LinkTypes::CAToSensemakerConfig, None)?;
+    links
+        .first()
+        .map(|link| {
+            link.target.clone().into_entry_hash().ok_or(wasm_error!(WasmErrorInner::Guest(
+                String::from("Invalid link target")
+            )))
+        })
+        .transpose()
+}
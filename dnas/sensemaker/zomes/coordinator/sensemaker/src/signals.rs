@@ -7,6 +7,11 @@ pub enum Signal {
     NewAssessment {
         assessment: Assessment,
     },
+    // an objective assessment recomputed because one of the method's inputs changed
+    LiveMethodResult {
+        method_eh: EntryHash,
+        assessment: Assessment,
+    },
 }
 
 #[hdk_extern]
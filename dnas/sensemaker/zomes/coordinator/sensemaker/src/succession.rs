@@ -0,0 +1,189 @@
+use hdk::prelude::*;
+use sensemaker_integrity::{
+    CommunityActivatorClaim, CommunityActivatorHandover, EntryTypes, HandoverApproval, LinkTypes,
+    Properties, Role, RoleClaim, RoleGrant, StewardApproval, UnitEntryTypes,
+};
+
+use crate::{
+    set_community_activator,
+    utils::{agent_chain_head, entry_from_record, get_records},
+};
+
+const COMMUNITY_ACTIVATOR_HANDOVERS: &str = "community_activator_handovers";
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct HandOverCommunityActivatorInput {
+    successor: AgentPubKey,
+    // only needed when the current community activator isn't the one handing over
+    steward_approvals: Vec<StewardApproval>,
+}
+
+#[hdk_extern]
+pub fn hand_over_community_activator(input: HandOverCommunityActivatorInput) -> ExternResult<Record> {
+    let succession = community_activator_succession()?;
+    let (_, latest_handover_ah) = succession.last().cloned().ok_or(wasm_error!(WasmErrorInner::Guest(
+        String::from("the succession always starts with the community activator in the DNA properties")
+    )))?;
+    let (previous_handover_ah, predecessor_chain_top) = match input.steward_approvals.first() {
+        None => (latest_handover_ah, None),
+        // a quorum hands over from the handover the stewards approved, pinning the chain of the community
+        // activator it hands over from so that validation can check they haven't handed over themselves
+        Some(approval) => {
+            let previous_handover_ah = approval.previous_handover_ah.clone();
+            if input
+                .steward_approvals
+                .iter()
+                .any(|approval| approval.previous_handover_ah != previous_handover_ah)
+            {
+                return Err(wasm_error!(WasmErrorInner::Guest(String::from(
+                    "the steward approvals hand over from different handovers"
+                ))));
+            }
+            let predecessor = succession
+                .into_iter()
+                .find(|(_, handover_ah)| *handover_ah == previous_handover_ah)
+                .map(|(community_activator, _)| community_activator)
+                .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+                    "the approved handover is not part of the succession"
+                ))))?;
+            (previous_handover_ah, Some(agent_chain_head(predecessor)?))
+        }
+    };
+    let handover = CommunityActivatorHandover {
+        successor: input.successor,
+        previous_handover_ah,
+        steward_approvals: input.steward_approvals,
+        predecessor_chain_top,
+    };
+    let action_hash = create_entry(&EntryTypes::CommunityActivatorHandover(handover))?;
+    create_link(
+        handovers_typed_path()?.path_entry_hash()?,
+        action_hash.clone(),
+        LinkTypes::CommunityActivatorHandovers,
+        (),
+    )?;
+    get(action_hash, GetOptions::default())?.ok_or(wasm_error!(WasmErrorInner::Guest(
+        String::from("not able to get handover record after create")
+    )))
+}
+
+// signs this steward's approval of handing over to `successor`, to be passed to whoever commits the handover
+#[hdk_extern]
+pub fn approve_community_activator_handover(successor: AgentPubKey) -> ExternResult<StewardApproval> {
+    let (_, previous_handover_ah) = resolve_community_activator()?;
+    let steward = agent_info()?.agent_latest_pubkey;
+    let role_claim_ah = find_steward_role_claim()?.ok_or(wasm_error!(WasmErrorInner::Guest(
+        String::from("only a steward can approve a community activator handover")
+    )))?;
+    let signature = sign(
+        steward.clone(),
+        HandoverApproval {
+            successor,
+            previous_handover_ah: previous_handover_ah.clone(),
+        },
+    )?;
+    Ok(StewardApproval {
+        steward,
+        role_claim_ah,
+        previous_handover_ah,
+        signature,
+    })
+}
+
+// the successor takes over by claiming the handover, and names themselves in the sensemaker config
+#[hdk_extern]
+pub fn claim_community_activator(handover_ah: ActionHash) -> ExternResult<Record> {
+    let action_hash = create_entry(&EntryTypes::CommunityActivatorClaim(CommunityActivatorClaim {
+        handover_ah,
+    }))?;
+    set_community_activator(agent_info()?.agent_latest_pubkey)?;
+    get(action_hash, GetOptions::default())?.ok_or(wasm_error!(WasmErrorInner::Guest(
+        String::from("not able to get community activator claim record after create")
+    )))
+}
+
+#[hdk_extern]
+pub fn get_current_community_activator(_: ()) -> ExternResult<AgentPubKey> {
+    Ok(resolve_community_activator()?.0)
+}
+
+// the current community activator and the last handover
+pub fn resolve_community_activator() -> ExternResult<(AgentPubKey, Option<ActionHash>)> {
+    community_activator_succession()?.pop().ok_or(wasm_error!(WasmErrorInner::Guest(
+        String::from("the succession always starts with the community activator in the DNA properties")
+    )))
+}
+
+// follows the handovers from the community activator in the DNA properties, returning every community
+// activator in turn with the handover that made them one. When several handovers follow the same one, the one
+// authored by the community activator it hands over from wins, then the one with the lowest action hash.
+pub fn community_activator_succession() -> ExternResult<Vec<(AgentPubKey, Option<ActionHash>)>> {
+    let links = get_links(
+        handovers_typed_path()?.path_entry_hash()?,
+        LinkTypes::CommunityActivatorHandovers,
+        None,
+    )?;
+    let handover_ahs = links
+        .into_iter()
+        .map(|link| {
+            link.target.into_action_hash().ok_or(wasm_error!(WasmErrorInner::Guest(
+                String::from("Invalid link target")
+            )))
+        })
+        .collect::<ExternResult<Vec<ActionHash>>>()?;
+    let records = get_records(handover_ahs.clone())?;
+    let mut handovers: Vec<(AgentPubKey, ActionHash, CommunityActivatorHandover)> = vec![];
+    for (handover_ah, maybe_record) in handover_ahs.into_iter().zip(records) {
+        if let Some(record) = maybe_record {
+            let author = record.action().author().clone();
+            handovers.push((
+                author,
+                handover_ah,
+                entry_from_record::<CommunityActivatorHandover>(record)?,
+            ));
+        }
+    }
+
+    let mut community_activator: AgentPubKey =
+        Properties::get()?.sensemaker_config.community_activator.into();
+    let mut last_handover_ah: Option<ActionHash> = None;
+    let mut succession = vec![(community_activator.clone(), None)];
+    loop {
+        let next_handover = handovers
+            .iter()
+            .filter(|(_, _, handover)| handover.previous_handover_ah == last_handover_ah)
+            .min_by_key(|(author, handover_ah, _)| (*author != community_activator, handover_ah.clone()));
+        match next_handover {
+            Some((_, handover_ah, handover)) => {
+                community_activator = handover.successor.clone();
+                last_handover_ah = Some(handover_ah.clone());
+                succession.push((community_activator.clone(), last_handover_ah.clone()));
+            }
+            None => break,
+        }
+    }
+    Ok(succession)
+}
+
+// the action hash of a claim on this agent's chain of a `Steward` role grant
+fn find_steward_role_claim() -> ExternResult<Option<ActionHash>> {
+    let role_claim_entry_type: EntryType = UnitEntryTypes::RoleClaim.try_into()?;
+    let records = query(
+        ChainQueryFilter::new()
+            .entry_type(role_claim_entry_type)
+            .include_entries(true),
+    )?;
+    for record in records {
+        let claim = entry_from_record::<RoleClaim>(record.clone())?;
+        if let Some(grant_record) = get(claim.grant_ah, GetOptions::default())? {
+            if entry_from_record::<RoleGrant>(grant_record)?.role == Role::Steward {
+                return Ok(Some(record.action_address().clone()));
+            }
+        }
+    }
+    Ok(None)
+}
+
+fn handovers_typed_path() -> ExternResult<TypedPath> {
+    Path::from(COMMUNITY_ACTIVATOR_HANDOVERS).typed(LinkTypes::CommunityActivatorHandovers)
+}
@@ -1,10 +1,10 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use hdk::prelude::*;
-use sensemaker_integrity::{Assessment, LinkTypes};
-pub use nh_sensemaker_zome_lib::entry_from_record;
+use sensemaker_integrity::{Assessment, Dimension, LinkTypes, Range};
+pub use nh_sensemaker_zome_lib::{entry_from_record, get_linked_records, get_links_batch, get_records};
 
-use crate::{assessment_typed_path, get_assessment};
+use crate::{assessment_typed_path, get_dimension, get_range, legacy_assessment_typed_path};
 
 // NOTE: when using the to get objective assessments, we need to clarify what it means for multiple objective assessments to be created for a resource
 // do we always assume the most up to date? how will these affect checking against thresholds?
@@ -12,29 +12,85 @@ pub fn get_assessments_for_resource_inner(
     resource_eh: EntryHash,
     dimension_ehs: Vec<EntryHash>,
 ) -> ExternResult<BTreeMap<EntryHash, Vec<Assessment>>> {
-    let mut assessments: BTreeMap<EntryHash, Vec<Assessment>> = BTreeMap::new();
+    Ok(get_assessments_for_resources_inner(vec![resource_eh.clone()], dimension_ehs)?
+        .remove(&resource_eh)
+        .unwrap_or_default())
+}
 
-    for dimension_eh in dimension_ehs {
-        let mut dimension_assessments: Vec<Assessment> = Vec::new();
-        let links = get_links(
-            assessment_typed_path(resource_eh.clone(), dimension_eh.clone())?.path_entry_hash()?,
-            LinkTypes::Assessment,
-            None,
-        )?;
-        for link in links {
-            let maybe_assessment = get_assessment(link.target.into_entry_hash()
-              .ok_or(wasm_error!(WasmErrorInner::Guest(String::from("Invalid link target"))))?
-            )?;
-            if let Some(record) = maybe_assessment {
-                let assessment = entry_from_record::<Assessment>(record)?;
-                dimension_assessments.push(assessment)
+// the assessments of each resource along each dimension, keyed by resource and then dimension. The links
+// are fetched in one call and the assessments in another, however many resources and dimensions there are
+pub fn get_assessments_for_resources_inner(
+    resource_ehs: Vec<EntryHash>,
+    dimension_ehs: Vec<EntryHash>,
+) -> ExternResult<BTreeMap<EntryHash, BTreeMap<EntryHash, Vec<Assessment>>>> {
+    let mut assessments: BTreeMap<EntryHash, BTreeMap<EntryHash, Vec<Assessment>>> = resource_ehs
+        .into_iter()
+        .map(|resource_eh| {
+            let dimension_assessments = dimension_ehs
+                .iter()
+                .map(|dimension_eh| (dimension_eh.clone(), vec![]))
+                .collect();
+            (resource_eh, dimension_assessments)
+        })
+        .collect();
+
+    let link_type = LinkTypes::Assessment.try_into_filter()?;
+    let mut keys: Vec<(EntryHash, EntryHash)> = vec![];
+    let mut get_links_inputs: Vec<GetLinksInput> = vec![];
+    for (resource_eh, dimension_assessments) in assessments.iter() {
+        for dimension_eh in dimension_assessments.keys() {
+            // assessments not yet migrated to their shard are still linked from the legacy path
+            for assessment_path in [
+                assessment_typed_path(resource_eh.clone(), dimension_eh.clone())?,
+                legacy_assessment_typed_path(resource_eh.clone(), dimension_eh.clone())?,
+            ] {
+                keys.push((resource_eh.clone(), dimension_eh.clone()));
+                get_links_inputs.push(GetLinksInput::new(
+                    assessment_path.path_entry_hash()?.into(),
+                    link_type.clone(),
+                    None,
+                ));
             }
         }
-        assessments.insert(dimension_eh.clone(), dimension_assessments);
+    }
+
+    let mut link_keys: Vec<(EntryHash, EntryHash)> = vec![];
+    let mut links: Vec<Link> = vec![];
+    for (key, path_links) in keys.into_iter().zip(get_links_batch(get_links_inputs)?) {
+        link_keys.extend(std::iter::repeat(key).take(path_links.len()));
+        links.extend(path_links);
+    }
+    // an assessment linked from both its shard and the legacy path is only fetched once
+    let mut seen: BTreeSet<AnyLinkableHash> = BTreeSet::new();
+    let (link_keys, links): (Vec<(EntryHash, EntryHash)>, Vec<Link>) = link_keys
+        .into_iter()
+        .zip(links)
+        .filter(|(_, link)| seen.insert(link.target.clone()))
+        .unzip();
+    for ((resource_eh, dimension_eh), maybe_record) in link_keys.into_iter().zip(get_linked_records(links)?) {
+        if let Some(record) = maybe_record {
+            let assessment = entry_from_record::<Assessment>(record)?;
+            assessments
+                .entry(resource_eh)
+                .or_default()
+                .entry(dimension_eh)
+                .or_default()
+                .push(assessment);
+        }
     }
     Ok(assessments)
 }
 
+// the links from every path, fetched in a single call
+pub fn get_links_from_paths(paths: Vec<TypedPath>, link_type: LinkTypes) -> ExternResult<Vec<Link>> {
+    let link_type = link_type.try_into_filter()?;
+    let inputs = paths
+        .into_iter()
+        .map(|path| Ok(GetLinksInput::new(path.path_entry_hash()?.into(), link_type.clone(), None)))
+        .collect::<ExternResult<Vec<GetLinksInput>>>()?;
+    Ok(get_links_batch(inputs)?.into_iter().flatten().collect())
+}
+
 // flatten a btree map into flat vec for convenience
 pub fn flatten_btree_map<K, V: Clone>(btree_map: BTreeMap<K, Vec<V>>) -> Vec<V> {
     btree_map
@@ -75,3 +131,24 @@ pub fn entry_hash_from_record(record: Record) -> ExternResult<EntryHash> {
         ))))?
         .clone())
 }
+
+pub fn get_range_for_dimension(dimension_eh: EntryHash) -> ExternResult<Range> {
+    let dimension_record = get_dimension(dimension_eh)?.ok_or(wasm_error!(WasmErrorInner::Guest(
+        String::from("Dimension Not Found")
+    )))?;
+    let dimension = entry_from_record::<Dimension>(dimension_record)?;
+    let range_record = get_range(dimension.range_eh)?.ok_or(wasm_error!(WasmErrorInner::Guest(
+        String::from("Range Not Found")
+    )))?;
+    entry_from_record::<Range>(range_record)
+}
+
+// the latest action on the agent's chain as the DHT sees it
+pub fn agent_chain_head(agent: AgentPubKey) -> ExternResult<ActionHash> {
+    match get_agent_activity(agent, ChainQueryFilter::new(), ActivityRequest::Status)?.status {
+        ChainStatus::Valid(chain_head) => Ok(chain_head.hash),
+        _ => Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "not able to get the agent's chain head"
+        )))),
+    }
+}
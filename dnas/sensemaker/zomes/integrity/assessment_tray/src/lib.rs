@@ -1,5 +1,5 @@
 use hdi::prelude::*;
-use sensemaker_integrity_structs::{Properties, AssessmentControlRegistration};
+use sensemaker_integrity_structs::{has_role, AssessmentControlRegistration, Role};
 
 #[derive(Debug, Clone, Serialize, Deserialize, SerializedBytes)]
 #[serde(rename_all = "camelCase")]
@@ -46,29 +46,29 @@ pub enum LinkTypes {
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
     return match op {
         Op::StoreEntry(entry) => match entry.action.hashed.clone().into_content() {
-            EntryCreationAction::Create(create) => validate_author_as_ca(
+            EntryCreationAction::Create(create) => validate_author_role(
                 OpTypes::StoreEntry(entry).clone(),
                 EntryCreationAction::Create(create).entry_type().clone(),
             ),
-            EntryCreationAction::Update(update) => validate_author_as_ca(
+            EntryCreationAction::Update(update) => validate_author_role(
                 OpTypes::StoreEntry(entry),
                 EntryCreationAction::Update(update).entry_type().clone(),
             ),
         },
         Op::StoreRecord(record) => {
             match record.record.signed_action.hashed.clone().into_content() {
-                Action::Create(create) => validate_author_as_ca(
+                Action::Create(create) => validate_author_role(
                     OpTypes::StoreRecord(record).clone(),
                     EntryCreationAction::Create(create).entry_type().clone(),
                 ),
-                Action::Update(update) => validate_author_as_ca(
+                Action::Update(update) => validate_author_role(
                     OpTypes::StoreRecord(record),
                     EntryCreationAction::Update(update).entry_type().clone(),
                 ),
                 _ => Ok(ValidateCallbackResult::Valid),
             }
         }
-        Op::RegisterUpdate(update) => validate_author_as_ca(
+        Op::RegisterUpdate(update) => validate_author_role(
             OpTypes::RegisterUpdate(update.clone()),
             update.update.hashed.entry_type.clone(),
         ),
@@ -76,7 +76,7 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
             let ah = delete.delete.hashed.deletes_address.clone();
             let action = must_get_action(ah)?;
             if let Some(entry_type) = action.hashed.entry_type() {
-                validate_author_as_ca(OpTypes::RegisterDelete(delete.clone()), entry_type.clone())
+                validate_author_role(OpTypes::RegisterDelete(delete.clone()), entry_type.clone())
             } else {
                 Ok(ValidateCallbackResult::Invalid(String::from(
                     "the delete address does not contain an entry type",
@@ -85,11 +85,11 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
         }
         Op::RegisterAgentActivity(activity) => {
             match activity.action.hashed.clone().into_content() {
-                Action::Create(create) => validate_author_as_ca(
+                Action::Create(create) => validate_author_role(
                     OpTypes::RegisterAgentActivity(activity).clone(),
                     EntryCreationAction::Create(create).entry_type().clone(),
                 ),
-                Action::Update(update) => validate_author_as_ca(
+                Action::Update(update) => validate_author_role(
                     OpTypes::RegisterAgentActivity(activity),
                     EntryCreationAction::Update(update).entry_type().clone(),
                 ),
@@ -97,7 +97,10 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
             }
         }
         Op::RegisterCreateLink { .. } => Ok(ValidateCallbackResult::Valid),
-        Op::RegisterDeleteLink { .. } => Ok(ValidateCallbackResult::Valid),
+        Op::RegisterDeleteLink(RegisterDeleteLink {
+            delete_link,
+            create_link,
+        }) => validate_delete_link(delete_link.hashed.content, create_link),
     };
 }
 
@@ -110,38 +113,83 @@ enum OpTypes {
     RegisterAgentActivity(RegisterAgentActivity),
 }
 
-// :DUPE: validate_author_as_ca
-fn validate_author_as_ca(
+// :DUPE: validate_author_role
+fn validate_author_role(
     op_types: OpTypes,
     entry_type: EntryType,
 ) -> ExternResult<ValidateCallbackResult> {
-    return match entry_type {
-        EntryType::App(app_entry_def) => {
-            return match app_entry_def.entry_index {
-                // AssessmentControlConfig
-                EntryDefIndex(0) => {
-                    let author = match op_types {
-                        OpTypes::StoreEntry(entry) => entry.clone().action.hashed.author().clone(),
-                        OpTypes::StoreRecord(record) => {
-                            record.record.signed_action.hashed.author().clone()
-                        }
-                        OpTypes::RegisterUpdate(update) => update.update.hashed.author.clone(),
-                        OpTypes::RegisterDelete(delete) => delete.delete.hashed.author.clone(),
-                        OpTypes::RegisterAgentActivity(activity) => {
-                            activity.action.hashed.author().clone()
-                        }
-                    };
-                    if let true = Properties::is_community_activator(author)? {
-                        Ok(ValidateCallbackResult::Valid)
-                    } else {
-                        Ok(ValidateCallbackResult::Invalid(String::from(
-                            "only the community activator can create this entry",
-                        )))
-                    }
-                }
-                _ => Ok(ValidateCallbackResult::Valid),
-            };
-        }
-        _ => Ok(ValidateCallbackResult::Valid),
+    let required_role = match entry_type {
+        EntryType::App(app_entry_def) => match app_entry_def.entry_index {
+            // AssessmentControlConfig
+            EntryDefIndex(0) => Role::TrayCurator,
+            _ => return Ok(ValidateCallbackResult::Valid),
+        },
+        _ => return Ok(ValidateCallbackResult::Valid),
+    };
+    let (author, prev_action, timestamp) = author_prev_action_and_timestamp(op_types);
+    if has_role(&author, &required_role, prev_action, timestamp)? {
+        Ok(ValidateCallbackResult::Valid)
+    } else {
+        Ok(ValidateCallbackResult::Invalid(format!(
+            "only the community activator or a {} can create this entry",
+            required_role
+        )))
+    }
+}
+
+// :DUPE: validate_delete_link
+// a tray curator replaces the default tray of a resource def whoever set it, other links can only be deleted
+// by their author or a steward
+fn validate_delete_link(
+    delete_link: DeleteLink,
+    create_link: CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+    if delete_link.author == create_link.author {
+        return Ok(ValidateCallbackResult::Valid);
+    }
+    let required_role = match LinkTypes::from_type(create_link.zome_index, create_link.link_type)? {
+        Some(LinkTypes::ResourceDefDefaultAssessmentTrayConfig) => Role::TrayCurator,
+        _ => Role::Steward,
     };
+    if has_role(
+        &delete_link.author,
+        &required_role,
+        Some(delete_link.prev_action),
+        delete_link.timestamp,
+    )? {
+        Ok(ValidateCallbackResult::Valid)
+    } else {
+        Ok(ValidateCallbackResult::Invalid(format!(
+            "only the author of a link or a {} can delete it",
+            required_role
+        )))
+    }
+}
+
+// :DUPE: author_prev_action_and_timestamp
+fn author_prev_action_and_timestamp(op_types: OpTypes) -> (AgentPubKey, Option<ActionHash>, Timestamp) {
+    match op_types {
+        OpTypes::StoreEntry(entry) => {
+            let action = entry.action.hashed.content;
+            (action.author().clone(), Some(action.prev_action().clone()), *action.timestamp())
+        }
+        OpTypes::StoreRecord(record) => {
+            let action = record.record.signed_action.hashed.content;
+            (action.author().clone(), action.prev_action().cloned(), action.timestamp())
+        }
+        OpTypes::RegisterUpdate(update) => (
+            update.update.hashed.author.clone(),
+            Some(update.update.hashed.prev_action.clone()),
+            update.update.hashed.timestamp,
+        ),
+        OpTypes::RegisterDelete(delete) => (
+            delete.delete.hashed.author.clone(),
+            Some(delete.delete.hashed.prev_action.clone()),
+            delete.delete.hashed.timestamp,
+        ),
+        OpTypes::RegisterAgentActivity(activity) => {
+            let action = activity.action.hashed.content;
+            (action.author().clone(), action.prev_action().cloned(), action.timestamp())
+        }
+    }
 }
@@ -0,0 +1,137 @@
+use std::collections::BTreeSet;
+
+use hdi::prelude::*;
+use sensemaker_integrity_structs::{Assessment, DataSet, Dimension, Method, Range, RangeKind, RangeValue};
+
+// how far an assessment's own timestamp may be from the timestamp of the action committing it
+const ASSESSMENT_TIMESTAMP_TOLERANCE_MICROS: u64 = 5 * 60 * 1_000_000;
+
+// a misbehaving client can't commit an assessment in another agent's name or backdate it
+pub fn validate_assessment(
+    assessment: Assessment,
+    action: EntryCreationAction,
+) -> ExternResult<ValidateCallbackResult> {
+    if assessment.author != *action.author() {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "an assessment's author must be the agent committing it",
+        )));
+    }
+    let drift = assessment.timestamp.as_micros().abs_diff(action.timestamp().as_micros());
+    if drift > ASSESSMENT_TIMESTAMP_TOLERANCE_MICROS {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "an assessment's timestamp must be within 5 minutes of the action committing it",
+        )));
+    }
+    if let EntryCreationAction::Update(update) = &action {
+        match validate_assessment_modification(update.original_action_address.clone(), &update.author)? {
+            ValidateCallbackResult::Valid => (),
+            invalid => return Ok(invalid),
+        }
+    }
+
+    // resolve the assessment's dimension and range from the DHT so a misbehaving client
+    // cannot commit a value outside of the dimension's range
+    let dimension = Dimension::try_from(must_get_entry(assessment.dimension_eh.clone())?.into_content())?;
+    let range = Range::try_from(must_get_entry(dimension.range_eh)?.into_content())?;
+    if let Err(error) = range.kind.check_value(&assessment.value) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "assessment value is invalid for dimension {}: {}",
+            dimension.name, error
+        )));
+    }
+    // methods outputting to a dimension that requires validation require it too, so citing
+    // another method can't skip recomputing the value
+    match assessment.maybe_input_dataset.clone() {
+        Some(dataset) => validate_input_dataset(assessment, dataset, &range.kind),
+        None if dimension.requires_validation => Ok(ValidateCallbackResult::Invalid(format!(
+            "assessments along dimension {} need a dataset to be recomputed from",
+            dimension.name
+        ))),
+        None => Ok(ValidateCallbackResult::Valid),
+    }
+}
+
+// assessments can only be updated or deleted by the agent that created them
+pub fn validate_assessment_modification(
+    original_action_hash: ActionHash,
+    author: &AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    if must_get_action(original_action_hash)?.hashed.author() == author {
+        Ok(ValidateCallbackResult::Valid)
+    } else {
+        Ok(ValidateCallbackResult::Invalid(String::from(
+            "assessments can only be updated or deleted by their author",
+        )))
+    }
+}
+
+// objective assessments from methods that require validation are recomputed from the assessments in
+// their dataset, so every agent can check the value without trusting the one who ran the method
+fn validate_input_dataset(
+    assessment: Assessment,
+    dataset: DataSet,
+    output_kind: &RangeKind,
+) -> ExternResult<ValidateCallbackResult> {
+    let method = Method::try_from(must_get_entry(dataset.from)?.into_content())?;
+    if method.output_dimension_eh != assessment.dimension_eh {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "method {} does not output to the assessment's dimension",
+            method.name
+        )));
+    }
+    if !method.requires_validation {
+        return Ok(ValidateCallbackResult::Valid);
+    }
+    let dataset_dimension_ehs: Vec<EntryHash> = dataset
+        .data_points
+        .iter()
+        .map(|(dimension_eh, _)| dimension_eh.clone())
+        .collect();
+    if dataset_dimension_ehs != method.input_dimension_ehs {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "dataset dimensions do not match the input dimensions of method {}",
+            method.name
+        )));
+    }
+
+    // the same assessment listed twice would count twice
+    let mut seen_assessment_ehs = BTreeSet::new();
+    let mut inputs: Vec<Vec<RangeValue>> = vec![];
+    for (dimension_eh, assessment_ehs) in dataset.data_points {
+        let mut values = vec![];
+        for assessment_eh in assessment_ehs {
+            if !seen_assessment_ehs.insert(assessment_eh.clone()) {
+                return Ok(ValidateCallbackResult::Invalid(String::from(
+                    "dataset lists the same assessment more than once",
+                )));
+            }
+            let input_assessment = Assessment::try_from(must_get_entry(assessment_eh)?.into_content())?;
+            if input_assessment.dimension_eh != dimension_eh
+                || input_assessment.resource_eh != assessment.resource_eh
+            {
+                return Ok(ValidateCallbackResult::Invalid(String::from(
+                    "dataset contains an assessment that is not on the listed dimension and resource",
+                )));
+            }
+            values.push(input_assessment.value);
+        }
+        inputs.push(values);
+    }
+    let computed_value = match method.program.compute(inputs, output_kind) {
+        Ok(value) => value,
+        Err(error) => {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "method {} could not be recomputed from the dataset: {:?}",
+                method.name, error
+            )))
+        }
+    };
+    if computed_value == assessment.value {
+        Ok(ValidateCallbackResult::Valid)
+    } else {
+        Ok(ValidateCallbackResult::Invalid(format!(
+            "assessment value {:?} does not match the value {:?} computed by method {}",
+            assessment.value, computed_value, method.name
+        )))
+    }
+}
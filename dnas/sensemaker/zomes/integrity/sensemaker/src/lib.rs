@@ -1,6 +1,14 @@
 use hdi::prelude::*;
 pub use sensemaker_integrity_structs::*;
 
+mod assessment;
+use assessment::{validate_assessment, validate_assessment_modification};
+mod primitives;
+use primitives::{
+    validate_context_result, validate_context_result_link, validate_cultural_context, validate_dimension,
+    validate_method, validate_range,
+};
+
 #[hdk_entry_defs]
 #[unit_enum(UnitEntryTypes)]
 pub enum EntryTypes {
@@ -16,6 +24,12 @@ pub enum EntryTypes {
     Range(Range),
     SensemakerConfig(SensemakerConfig),
     AppletConfig(AppletConfig),
+    RoleGrant(RoleGrant),
+    RoleClaim(RoleClaim),
+    CommunityActivatorHandover(CommunityActivatorHandover),
+    CommunityActivatorClaim(CommunityActivatorClaim),
+    RoleRevocation(RoleRevocation),
+    RoleCheckpoint(RoleCheckpoint),
 }
 
 #[hdk_link_types]
@@ -31,35 +45,46 @@ pub enum LinkTypes {
     AppletConfig,
     ResourceDefEhToAppletConfig,
     AllAgentsPath,
+    CulturalContextToContextResult,
+    CulturalContexts,
+    ResourceDefToCulturalContext,
+    AgentToRoleGrant,
+    CommunityActivatorHandovers,
+    AgentToAssessment,
+    AssessmentTimeBuckets,
+    ResourceDefToAssessment,
+    AgentToRoleRevocation,
 }
 
 #[hdk_extern]
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
     return match op {
-        Op::StoreEntry(entry) => match entry.action.hashed.clone().into_content() {
-            EntryCreationAction::Create(create) => validate_author_as_ca(
-                OpTypes::StoreEntry(entry).clone(),
-                EntryCreationAction::Create(create).entry_type().clone(),
-            ),
-            EntryCreationAction::Update(update) => validate_author_as_ca(
-                OpTypes::StoreEntry(entry),
-                EntryCreationAction::Update(update).entry_type().clone(),
-            ),
-        },
+        Op::StoreEntry(entry) => {
+            let entry_type = match entry.action.hashed.clone().into_content() {
+                EntryCreationAction::Create(create) => create.entry_type,
+                EntryCreationAction::Update(update) => update.entry_type,
+            };
+            // content is only checked where the entry is stored, once the author is known to be allowed to write it
+            let action = entry.action.hashed.content.clone();
+            match validate_author_role(OpTypes::StoreEntry(entry.clone()), entry_type.clone())? {
+                ValidateCallbackResult::Valid => validate_entry_content(entry.entry, entry_type, action),
+                invalid => Ok(invalid),
+            }
+        }
         Op::StoreRecord(record) => {
             match record.record.signed_action.hashed.clone().into_content() {
-                Action::Create(create) => validate_author_as_ca(
+                Action::Create(create) => validate_author_role(
                     OpTypes::StoreRecord(record).clone(),
                     EntryCreationAction::Create(create).entry_type().clone(),
                 ),
-                Action::Update(update) => validate_author_as_ca(
+                Action::Update(update) => validate_author_role(
                     OpTypes::StoreRecord(record),
                     EntryCreationAction::Update(update).entry_type().clone(),
                 ),
                 _ => Ok(ValidateCallbackResult::Valid),
             }
         }
-        Op::RegisterUpdate(update) => validate_author_as_ca(
+        Op::RegisterUpdate(update) => validate_author_role(
             OpTypes::RegisterUpdate(update.clone()),
             update.update.hashed.entry_type.clone(),
         ),
@@ -67,7 +92,7 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
             let ah = delete.delete.hashed.deletes_address.clone();
             let action = must_get_action(ah)?;
             if let Some(entry_type) = action.hashed.entry_type() {
-                validate_author_as_ca(OpTypes::RegisterDelete(delete.clone()), entry_type.clone())
+                validate_author_role(OpTypes::RegisterDelete(delete.clone()), entry_type.clone())
             } else {
                 Ok(ValidateCallbackResult::Invalid(String::from(
                     "the delete address does not contain an entry type",
@@ -76,19 +101,24 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
         }
         Op::RegisterAgentActivity(activity) => {
             match activity.action.hashed.clone().into_content() {
-                Action::Create(create) => validate_author_as_ca(
+                Action::Create(create) => validate_author_role(
                     OpTypes::RegisterAgentActivity(activity).clone(),
                     EntryCreationAction::Create(create).entry_type().clone(),
                 ),
-                Action::Update(update) => validate_author_as_ca(
+                Action::Update(update) => validate_author_role(
                     OpTypes::RegisterAgentActivity(activity),
                     EntryCreationAction::Update(update).entry_type().clone(),
                 ),
                 _ => Ok(ValidateCallbackResult::Valid),
             }
         }
-        Op::RegisterCreateLink { .. } => Ok(ValidateCallbackResult::Valid),
-        Op::RegisterDeleteLink { .. } => Ok(ValidateCallbackResult::Valid),
+        Op::RegisterCreateLink(RegisterCreateLink { create_link }) => {
+            validate_create_link(create_link.hashed.content)
+        }
+        Op::RegisterDeleteLink(RegisterDeleteLink {
+            delete_link,
+            create_link,
+        }) => validate_delete_link(delete_link.hashed.content, create_link),
     };
 }
 
@@ -101,38 +131,307 @@ enum OpTypes {
     RegisterAgentActivity(RegisterAgentActivity),
 }
 
-fn validate_author_as_ca(
+// the community activator can write every entry, other agents need to have claimed the entry's role
+fn validate_author_role(
     op_types: OpTypes,
     entry_type: EntryType,
 ) -> ExternResult<ValidateCallbackResult> {
+    let required_role = match entry_type {
+        EntryType::App(app_entry_def) => match app_entry_def.entry_index {
+            // assessment, anyone can create one but only its author can update or delete it
+            EntryDefIndex(0) => {
+                return match op_types {
+                    OpTypes::RegisterUpdate(update) => validate_assessment_modification(
+                        update.update.hashed.original_action_address.clone(),
+                        &update.update.hashed.author,
+                    ),
+                    OpTypes::RegisterDelete(delete) => validate_assessment_modification(
+                        delete.delete.hashed.deletes_address.clone(),
+                        &delete.delete.hashed.author,
+                    ),
+                    _ => Ok(ValidateCallbackResult::Valid),
+                };
+            }
+            // cultura context, context result, dimension, resource type, range, applet config
+            EntryDefIndex(1) | EntryDefIndex(2) | EntryDefIndex(4) | EntryDefIndex(7)
+            | EntryDefIndex(8) | EntryDefIndex(10) => Role::Steward,
+            // method
+            EntryDefIndex(5) => Role::MethodAuthor,
+            // sensemaker config
+            EntryDefIndex(9) => {
+                if let OpTypes::RegisterDelete(_) = op_types {
+                    return Ok(ValidateCallbackResult::Invalid(String::from(
+                        "the sensemaker config can't be deleted",
+                    )));
+                }
+                let (author, prev_action, _) = author_prev_action_and_timestamp(op_types);
+                return if is_community_activator_at(&author, prev_action)? {
+                    Ok(ValidateCallbackResult::Valid)
+                } else {
+                    Ok(ValidateCallbackResult::Invalid(String::from(
+                        "only the community activator can create or update the sensemaker config",
+                    )))
+                };
+            }
+            // role grant
+            EntryDefIndex(11) => {
+                let (author, prev_action, _) = author_prev_action_and_timestamp(op_types);
+                return if is_community_activator_at(&author, prev_action)? {
+                    Ok(ValidateCallbackResult::Valid)
+                } else {
+                    Ok(ValidateCallbackResult::Invalid(String::from(
+                        "only the community activator can grant roles",
+                    )))
+                };
+            }
+            // role revocation
+            EntryDefIndex(15) => {
+                let (author, prev_action, _) = author_prev_action_and_timestamp(op_types);
+                return if is_community_activator_at(&author, prev_action)? {
+                    Ok(ValidateCallbackResult::Valid)
+                } else {
+                    Ok(ValidateCallbackResult::Invalid(String::from(
+                        "only the community activator can revoke roles",
+                    )))
+                };
+            }
+            _ => return Ok(ValidateCallbackResult::Valid),
+        },
+        _ => return Ok(ValidateCallbackResult::Valid),
+    };
+    let (author, prev_action, timestamp) = author_prev_action_and_timestamp(op_types);
+    if has_role(&author, &required_role, prev_action, timestamp)? {
+        Ok(ValidateCallbackResult::Valid)
+    } else {
+        Ok(ValidateCallbackResult::Invalid(format!(
+            "only the community activator or a {} can create this entry",
+            required_role
+        )))
+    }
+}
+
+// only a steward can point a context to a published result, so that the latest result can be trusted
+fn validate_create_link(create_link: CreateLink) -> ExternResult<ValidateCallbackResult> {
+    match LinkTypes::from_type(create_link.zome_index, create_link.link_type)? {
+        Some(LinkTypes::CulturalContextToContextResult) => {
+            if !has_role(
+                &create_link.author,
+                &Role::Steward,
+                Some(create_link.prev_action),
+                create_link.timestamp,
+            )? {
+                return Ok(ValidateCallbackResult::Invalid(String::from(
+                    "only the community activator or a steward can publish a context result",
+                )));
+            }
+            validate_context_result_link(create_link.base_address, create_link.target_address)
+        }
+        _ => Ok(ValidateCallbackResult::Valid),
+    }
+}
+
+// links can be deleted by their author, or by a steward tidying up after others, e.g. when unregistering an applet
+fn validate_delete_link(
+    delete_link: DeleteLink,
+    create_link: CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+    if delete_link.author == create_link.author
+        || has_role(
+            &delete_link.author,
+            &Role::Steward,
+            Some(delete_link.prev_action),
+            delete_link.timestamp,
+        )?
+    {
+        Ok(ValidateCallbackResult::Valid)
+    } else {
+        Ok(ValidateCallbackResult::Invalid(String::from(
+            "only the author of a link or a steward can delete it",
+        )))
+    }
+}
+
+// roles are looked up on the author's chain before the action, as of when it was committed
+fn author_prev_action_and_timestamp(op_types: OpTypes) -> (AgentPubKey, Option<ActionHash>, Timestamp) {
+    match op_types {
+        OpTypes::StoreEntry(entry) => {
+            let action = entry.action.hashed.content;
+            (action.author().clone(), Some(action.prev_action().clone()), *action.timestamp())
+        }
+        OpTypes::StoreRecord(record) => {
+            let action = record.record.signed_action.hashed.content;
+            (action.author().clone(), action.prev_action().cloned(), action.timestamp())
+        }
+        OpTypes::RegisterUpdate(update) => (
+            update.update.hashed.author.clone(),
+            Some(update.update.hashed.prev_action.clone()),
+            update.update.hashed.timestamp,
+        ),
+        OpTypes::RegisterDelete(delete) => (
+            delete.delete.hashed.author.clone(),
+            Some(delete.delete.hashed.prev_action.clone()),
+            delete.delete.hashed.timestamp,
+        ),
+        OpTypes::RegisterAgentActivity(activity) => {
+            let action = activity.action.hashed.content;
+            (action.author().clone(), action.prev_action().cloned(), action.timestamp())
+        }
+    }
+}
+
+fn validate_entry_content(
+    entry: Entry,
+    entry_type: EntryType,
+    action: EntryCreationAction,
+) -> ExternResult<ValidateCallbackResult> {
+    let author = action.author().clone();
     return match entry_type {
         EntryType::App(app_entry_def) => {
             return match app_entry_def.entry_index {
-                // cultura context, dimension, method, resource type, range
-                EntryDefIndex(1) | EntryDefIndex(4) | EntryDefIndex(5) | EntryDefIndex(7)
-                | EntryDefIndex(8) => {
-                    let author = match op_types {
-                        OpTypes::StoreEntry(entry) => entry.clone().action.hashed.author().clone(),
-                        OpTypes::StoreRecord(record) => {
-                            record.record.signed_action.hashed.author().clone()
-                        }
-                        OpTypes::RegisterUpdate(update) => update.update.hashed.author.clone(),
-                        OpTypes::RegisterDelete(delete) => delete.delete.hashed.author.clone(),
-                        OpTypes::RegisterAgentActivity(activity) => {
-                            activity.action.hashed.author().clone()
-                        }
-                    };
-                    if let true = Properties::is_community_activator(author)? {
-                        Ok(ValidateCallbackResult::Valid)
-                    } else {
-                        Ok(ValidateCallbackResult::Invalid(String::from(
-                            "only the community activator can create this entry",
-                        )))
-                    }
-                }
+                // assessment
+                EntryDefIndex(0) => validate_assessment(Assessment::try_from(entry)?, action),
+                // cultural context
+                EntryDefIndex(1) => validate_cultural_context(CulturalContext::try_from(entry)?),
+                // context result
+                EntryDefIndex(2) => validate_context_result(ContextResult::try_from(entry)?),
+                // dimension
+                EntryDefIndex(4) => validate_dimension(Dimension::try_from(entry)?),
+                // method
+                EntryDefIndex(5) => validate_method(Method::try_from(entry)?),
+                // range
+                EntryDefIndex(8) => validate_range(Range::try_from(entry)?),
+                // sensemaker config
+                EntryDefIndex(9) => validate_sensemaker_config(SensemakerConfig::try_from(entry)?, action),
+                // role claim
+                EntryDefIndex(12) => match RoleClaim::try_from(entry)?.grant_for(&author)? {
+                    Ok(_) => Ok(ValidateCallbackResult::Valid),
+                    Err(error) => Ok(ValidateCallbackResult::Invalid(error)),
+                },
+                // community activator handover
+                EntryDefIndex(13) => match CommunityActivatorHandover::try_from(entry)?
+                    .check(&author, Some(action.prev_action().clone()))?
+                {
+                    Ok(()) => Ok(ValidateCallbackResult::Valid),
+                    Err(error) => Ok(ValidateCallbackResult::Invalid(error)),
+                },
+                // community activator claim
+                EntryDefIndex(14) => match CommunityActivatorClaim::try_from(entry)?.check(&author)? {
+                    Ok(()) => Ok(ValidateCallbackResult::Valid),
+                    Err(error) => Ok(ValidateCallbackResult::Invalid(error)),
+                },
+                // role revocation
+                EntryDefIndex(15) => match RoleRevocation::try_from(entry)?.grant()? {
+                    Ok(_) => Ok(ValidateCallbackResult::Valid),
+                    Err(error) => Ok(ValidateCallbackResult::Invalid(error)),
+                },
+                // role checkpoint
+                EntryDefIndex(16) => match RoleCheckpoint::try_from(entry)?.pinned_action()? {
+                    Ok(_) => Ok(ValidateCallbackResult::Valid),
+                    Err(error) => Ok(ValidateCallbackResult::Invalid(error)),
+                },
                 _ => Ok(ValidateCallbackResult::Valid),
             };
         }
         _ => Ok(ValidateCallbackResult::Valid),
     };
 }
+
+// updates have to chain from the original config created by the community activator in the DNA properties,
+// can't take the wizard version back from the config they replace, and keep the neighbourhood
+fn validate_sensemaker_config(
+    config: SensemakerConfig,
+    action: EntryCreationAction,
+) -> ExternResult<ValidateCallbackResult> {
+    if let Err(error) = config.check_format() {
+        return Ok(ValidateCallbackResult::Invalid(error));
+    }
+    if AgentPubKey::from(config.community_activator.clone()) != *action.author() {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "the sensemaker config must name its author as the community activator",
+        )));
+    }
+    let update = match action {
+        EntryCreationAction::Create(_) => return Ok(ValidateCallbackResult::Valid),
+        EntryCreationAction::Update(update) => update,
+    };
+    let original_action = must_get_action(update.original_action_address)?;
+    let updates_original_config = match original_action.hashed.content {
+        Action::Create(create) => Properties::is_community_activator(create.author)?,
+        _ => false,
+    };
+    if !updates_original_config {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "sensemaker config updates must update the original config created by the community activator",
+        )));
+    }
+    let original_config =
+        SensemakerConfig::try_from(must_get_entry(update.original_entry_address)?.into_content())?;
+    let replaced_config = replaced_sensemaker_config(&update.author, update.prev_action, original_config)?;
+    if config.wizard_version_numbers() < replaced_config.wizard_version_numbers() {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "wizard_version {} is lower than the replaced config's {}",
+            config.wizard_version, replaced_config.wizard_version
+        )));
+    }
+    if config.neighbourhood != replaced_config.neighbourhood {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "the neighbourhood can't be changed from {}",
+            replaced_config.neighbourhood
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// the config an update by `author` replaces: the latest config on their chain up to `chain_top` since they
+// became the community activator, or else the latest one their predecessor wrote before handing over to them,
+// and so on back to the original config. A handover approved by stewards isn't on the predecessor's chain,
+// so the original config is the one replaced after it.
+fn replaced_sensemaker_config(
+    author: &AgentPubKey,
+    chain_top: ActionHash,
+    original_config: SensemakerConfig,
+) -> ExternResult<SensemakerConfig> {
+    let sensemaker_config_type: EntryType = UnitEntryTypes::SensemakerConfig.try_into()?;
+    let claim_type: EntryType = UnitEntryTypes::CommunityActivatorClaim.try_into()?;
+    let mut agent = author.clone();
+    let mut chain_top = chain_top;
+    loop {
+        let mut activities = must_get_agent_activity(agent.clone(), ChainFilter::new(chain_top))?;
+        activities.sort_by_key(|activity| activity.action.hashed.content.action_seq());
+        let mut latest_config_eh = None;
+        let mut claim_eh = None;
+        for activity in activities {
+            let (entry_type, entry_hash) = match activity.action.hashed.content.entry_data() {
+                Some((entry_hash, entry_type)) => (entry_type.clone(), entry_hash.clone()),
+                None => continue,
+            };
+            if entry_type == sensemaker_config_type {
+                latest_config_eh = Some(entry_hash);
+            } else if entry_type == claim_type {
+                latest_config_eh = None;
+                claim_eh = Some(entry_hash);
+            }
+        }
+        if let Some(latest_config_eh) = latest_config_eh {
+            return SensemakerConfig::try_from(must_get_entry(latest_config_eh)?.into_content());
+        }
+        let claim = match claim_eh {
+            Some(claim_eh) => CommunityActivatorClaim::try_from(must_get_entry(claim_eh)?.into_content())?,
+            None => return Ok(original_config),
+        };
+        let handover_record = must_get_valid_record(claim.handover_ah.clone())?;
+        let handover: CommunityActivatorHandover = handover_record
+            .entry()
+            .to_app_option()
+            .map_err(|err| wasm_error!(err))?
+            .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+                "the claimed action does not create a community activator handover"
+            ))))?;
+        let predecessor = handover.predecessor()?;
+        if *handover_record.action().author() != predecessor {
+            return Ok(original_config);
+        }
+        agent = predecessor;
+        chain_top = claim.handover_ah;
+    }
+}
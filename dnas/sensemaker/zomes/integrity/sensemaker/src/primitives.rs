@@ -0,0 +1,230 @@
+use hdi::prelude::*;
+use sensemaker_integrity_structs::{
+    ConfigProblem, ContextResult, CulturalContext, Dimension, Method, Range, ResourceDef,
+};
+
+// the same structural checks `AppletConfigInput::problems` runs on a config, enforced on the entries
+// themselves by resolving the entries they reference from the DHT
+
+pub fn validate_range(range: Range) -> ExternResult<ValidateCallbackResult> {
+    match range.kind.check_bounds() {
+        Ok(()) => Ok(ValidateCallbackResult::Valid),
+        Err(error) => Ok(ValidateCallbackResult::Invalid(
+            ConfigProblem::InvalidRange {
+                range: range.name,
+                error,
+            }
+            .to_string(),
+        )),
+    }
+}
+
+pub fn validate_dimension(dimension: Dimension) -> ExternResult<ValidateCallbackResult> {
+    if must_get_app_entry::<Range>(dimension.range_eh)?.is_none() {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "dimension {} has a range_eh that is not a range",
+            dimension.name
+        )));
+    }
+    if dimension.requires_validation && !dimension.computed {
+        return Ok(ValidateCallbackResult::Invalid(
+            ConfigProblem::SubjectiveValidatedDimension {
+                dimension: dimension.name,
+            }
+            .to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_method(method: Method) -> ExternResult<ValidateCallbackResult> {
+    let mut input_range_kinds = vec![];
+    for dimension_eh in method.input_dimension_ehs.iter() {
+        let (dimension, range) = match must_get_dimension_and_range(dimension_eh.clone())? {
+            Some(dimension_and_range) => dimension_and_range,
+            None => return Ok(not_a_dimension(format!("method {}", method.name))),
+        };
+        if dimension.computed {
+            return Ok(ValidateCallbackResult::Invalid(
+                ConfigProblem::ObjectiveInputDimension {
+                    method: method.name,
+                    dimension: dimension.name,
+                }
+                .to_string(),
+            ));
+        }
+        input_range_kinds.push(range.kind);
+    }
+    let (output_dimension, output_range) =
+        match must_get_dimension_and_range(method.output_dimension_eh.clone())? {
+            Some(dimension_and_range) => dimension_and_range,
+            None => return Ok(not_a_dimension(format!("method {}", method.name))),
+        };
+    if !output_dimension.computed {
+        return Ok(ValidateCallbackResult::Invalid(
+            ConfigProblem::SubjectiveOutputDimension {
+                method: method.name,
+                dimension: output_dimension.name,
+            }
+            .to_string(),
+        ));
+    }
+    if let Err(error) = method.program.type_check(&input_range_kinds, &output_range.kind) {
+        return Ok(ValidateCallbackResult::Invalid(
+            ConfigProblem::InvalidProgram {
+                method: method.name,
+                error,
+            }
+            .to_string(),
+        ));
+    }
+    if method.requires_validation != output_dimension.requires_validation {
+        return Ok(ValidateCallbackResult::Invalid(
+            ConfigProblem::MethodValidationMismatch {
+                method: method.name,
+                dimension: output_dimension.name,
+            }
+            .to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+pub fn validate_cultural_context(context: CulturalContext) -> ExternResult<ValidateCallbackResult> {
+    if must_get_app_entry::<ResourceDef>(context.resource_def_eh.clone())?.is_none() {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "cultural context {} has a resource_def_eh that is not a resource def",
+            context.name
+        )));
+    }
+
+    let mut threshold_dimension_ehs: Vec<EntryHash> = context
+        .thresholds
+        .iter()
+        .map(|threshold| threshold.dimension_eh.clone())
+        .collect();
+    if let Some(condition) = &context.threshold_condition {
+        threshold_dimension_ehs.extend(
+            condition
+                .thresholds()
+                .into_iter()
+                .map(|threshold| threshold.dimension_eh.clone()),
+        );
+    }
+    for dimension_eh in threshold_dimension_ehs {
+        let dimension = match must_get_app_entry::<Dimension>(dimension_eh)? {
+            Some(dimension) => dimension,
+            None => return Ok(not_a_dimension(format!("cultural context {}", context.name))),
+        };
+        if !dimension.computed {
+            return Ok(ValidateCallbackResult::Invalid(
+                ConfigProblem::SubjectiveThresholdDimension {
+                    context: context.name,
+                    dimension: dimension.name,
+                }
+                .to_string(),
+            ));
+        }
+    }
+
+    for (dimension_eh, _) in context.order_by.iter() {
+        let dimension = match must_get_app_entry::<Dimension>(dimension_eh.clone())? {
+            Some(dimension) => dimension,
+            None => return Ok(not_a_dimension(format!("cultural context {}", context.name))),
+        };
+        if !dimension.computed {
+            return Ok(ValidateCallbackResult::Invalid(
+                ConfigProblem::SubjectiveOrderByDimension {
+                    context: context.name,
+                    dimension: dimension.name,
+                }
+                .to_string(),
+            ));
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// a published result has to be shaped by the context it is published for: one value per ordering dimension
+pub fn validate_context_result(context_result: ContextResult) -> ExternResult<ValidateCallbackResult> {
+    let context = match must_get_app_entry::<CulturalContext>(context_result.context_eh)? {
+        Some(context) => context,
+        None => {
+            return Ok(ValidateCallbackResult::Invalid(String::from(
+                "context result has a context_eh that is not a cultural context",
+            )))
+        }
+    };
+    let order_by_dimension_ehs: Vec<EntryHash> = context
+        .order_by
+        .into_iter()
+        .map(|(dimension_eh, _)| dimension_eh)
+        .collect();
+    if context_result.dimension_ehs != order_by_dimension_ehs {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "context result dimensions do not match the order_by dimensions of cultural context {}",
+            context.name
+        )));
+    }
+    if context_result
+        .result
+        .iter()
+        .any(|(_, values)| values.len() != order_by_dimension_ehs.len())
+    {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "context result must hold one value per dimension for every resource",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// results are linked from the context they were computed for
+pub fn validate_context_result_link(
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+) -> ExternResult<ValidateCallbackResult> {
+    let context_result_eh = match target_address.into_entry_hash() {
+        Some(entry_hash) => entry_hash,
+        None => {
+            return Ok(ValidateCallbackResult::Invalid(String::from(
+                "a context result link must point to a context result entry",
+            )))
+        }
+    };
+    let context_result = match must_get_app_entry::<ContextResult>(context_result_eh)? {
+        Some(context_result) => context_result,
+        None => {
+            return Ok(ValidateCallbackResult::Invalid(String::from(
+                "a context result link must point to a context result entry",
+            )))
+        }
+    };
+    if AnyLinkableHash::from(context_result.context_eh) != base_address {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "a context result can only be linked from the cultural context it was computed for",
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn not_a_dimension(referrer: String) -> ValidateCallbackResult {
+    ValidateCallbackResult::Invalid(format!(
+        "{} references a dimension_eh that is not a dimension",
+        referrer
+    ))
+}
+
+fn must_get_dimension_and_range(dimension_eh: EntryHash) -> ExternResult<Option<(Dimension, Range)>> {
+    let dimension = match must_get_app_entry::<Dimension>(dimension_eh)? {
+        Some(dimension) => dimension,
+        None => return Ok(None),
+    };
+    // dimensions are validated to reference a range, so this one can be trusted
+    let range = Range::try_from(must_get_entry(dimension.range_eh.clone())?.into_content())?;
+    Ok(Some((dimension, range)))
+}
+
+// `None` when the entry exists but isn't a `T`
+fn must_get_app_entry<T: TryFrom<Entry>>(entry_hash: EntryHash) -> ExternResult<Option<T>> {
+    Ok(T::try_from(must_get_entry(entry_hash)?.into_content()).ok())
+}
@@ -1,9 +1,9 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use hdi::prelude::*;
 
 use crate::{
-    CulturalContext, Dimension, Method, OrderingKind, Program, Range, RangeValue,
+    Dimension, OrderingKind, Program, Range, RangeKind, RangeValue, ResourceDef, ThresholdCondition,
     ThresholdKind,
 };
 
@@ -19,6 +19,16 @@ pub struct AppletConfig {
     pub resource_defs: BTreeMap<String, EntryHash>,
     pub methods: BTreeMap<String, EntryHash>,
     pub cultural_contexts: BTreeMap<String, EntryHash>,
+    // incremented by every update, configs registered before versioning are read as the first version
+    #[serde(default = "first_applet_config_version")]
+    pub version: u32,
+    // the version this config replaced, `None` for the first version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_config_eh: Option<EntryHash>,
+}
+
+pub fn first_applet_config_version() -> u32 {
+    1
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -35,59 +45,206 @@ pub struct AppletConfigInput {
 }
 
 impl AppletConfigInput {
+    // rejects the config with every problem found, so that it can be fixed in one pass
     pub fn check_format(self) -> ExternResult<()> {
+        let problems = self.problems()?;
+        if problems.is_empty() {
+            return Ok(());
+        }
+        let error = format!(
+            "applet config {} is invalid: {}",
+            self.name,
+            problems
+                .iter()
+                .map(|problem| problem.to_string())
+                .collect::<Vec<String>>()
+                .join("; ")
+        );
+        Err(wasm_error!(WasmErrorInner::Guest(error)))
+    }
+
+    pub fn problems(&self) -> ExternResult<Vec<ConfigProblem>> {
+        let mut problems = vec![];
+        problems.extend(duplicate_names("range", self.ranges.iter().map(|range| &range.name)));
+        problems.extend(duplicate_names(
+            "dimension",
+            self.dimensions.iter().map(|dimension| &dimension.name),
+        ));
+        problems.extend(duplicate_names(
+            "resource def",
+            self.resource_defs.iter().map(|resource_def| &resource_def.resource_name),
+        ));
+        problems.extend(duplicate_names("method", self.methods.iter().map(|method| &method.name)));
+        problems.extend(duplicate_names(
+            "cultural context",
+            self.cultural_contexts.iter().map(|context| &context.name),
+        ));
+
+        for range in self.ranges.iter() {
+            if let Err(error) = range.kind.check_bounds() {
+                problems.push(ConfigProblem::InvalidRange {
+                    range: range.name.clone(),
+                    error,
+                });
+            }
+        }
+
         // convert all ranges in config to EntryHashes
         let range_ehs = self
             .ranges
-            .into_iter()
-            .map(|range| hash_entry(range))
+            .iter()
+            .map(|range| hash_entry(range.clone()))
             .collect::<ExternResult<Vec<EntryHash>>>()?;
-
-        // check if all dimensions are valid and convert to EntryHashes
-        let dimension_ehs = self
-            .dimensions
-            .clone()
-            .into_iter()
-            .map(|dimension| dimension.check_format(range_ehs.clone()))
+        let mut dimension_ehs = vec![];
+        for dimension in self.dimensions.iter() {
+            problems.extend(dimension.problems(&range_ehs)?);
+            dimension_ehs.push(hash_entry(Dimension::try_from(dimension.clone())?)?);
+        }
+        let resource_def_ehs = self
+            .resource_defs
+            .iter()
+            .map(|resource_def| hash_entry(ResourceDef::try_from(resource_def.clone())?))
             .collect::<ExternResult<Vec<EntryHash>>>()?;
 
-        // Mapping to detect errors. There may be better ways to handle this other than map
-        let _check_result_methods: Vec<bool> = self
-            .methods
-            .into_iter()
-            .map(|method| method.check_format(dimension_ehs.clone()))
-            .collect::<ExternResult<Vec<bool>>>()?;
-        let _check_result_contexts: Vec<bool> = self
-            .cultural_contexts
-            .into_iter()
-            .map(|context| context.check_format(dimension_ehs.clone()))
-            .collect::<ExternResult<Vec<bool>>>()?;
-
-        Ok(())
+        for method in self.methods.iter() {
+            problems.extend(method.problems(&dimension_ehs)?);
+        }
+        for context in self.cultural_contexts.iter() {
+            problems.extend(context.problems(&dimension_ehs, &resource_def_ehs)?);
+        }
+        Ok(problems)
     }
 }
 
+// a problem found in an applet config by `AppletConfigInput::problems`
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum ConfigProblem {
+    DuplicateName { kind: String, name: String },
+    InvalidRange { range: String, error: String },
+    UnknownRange { dimension: String },
+    SubjectiveValidatedDimension { dimension: String },
+    InvalidProgram { method: String, error: String },
+    ObjectiveInputDimension { method: String, dimension: String },
+    SubjectiveOutputDimension { method: String, dimension: String },
+    MethodValidationMismatch { method: String, dimension: String },
+    UnknownMethodDimension { method: String, dimension: String },
+    UnknownResourceDef { context: String, resource_def: String },
+    DanglingThresholdDimension { context: String, dimension: String },
+    SubjectiveThresholdDimension { context: String, dimension: String },
+    UnknownOrderByDimension { context: String, dimension: String },
+    SubjectiveOrderByDimension { context: String, dimension: String },
+}
+
+impl std::fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigProblem::DuplicateName { kind, name } => {
+                write!(f, "more than one {} is named {}", kind, name)
+            }
+            ConfigProblem::InvalidRange { range, error } => {
+                write!(f, "range {} is invalid: {}", range, error)
+            }
+            ConfigProblem::UnknownRange { dimension } => {
+                write!(f, "dimension {} has a range not found in root ranges", dimension)
+            }
+            ConfigProblem::SubjectiveValidatedDimension { dimension } => write!(
+                f,
+                "dimension {} requires validation, only objective dimensions can",
+                dimension
+            ),
+            ConfigProblem::InvalidProgram { method, error } => {
+                write!(f, "method {} has an invalid program: {}", method, error)
+            }
+            ConfigProblem::ObjectiveInputDimension { method, dimension } => write!(
+                f,
+                "method {} has objective input dimension {}, input dimensions must be subjective",
+                method, dimension
+            ),
+            ConfigProblem::SubjectiveOutputDimension { method, dimension } => write!(
+                f,
+                "method {} has subjective output dimension {}, the output dimension must be objective",
+                method, dimension
+            ),
+            ConfigProblem::MethodValidationMismatch { method, dimension } => write!(
+                f,
+                "method {} and its output dimension {} must agree on requires_validation",
+                method, dimension
+            ),
+            ConfigProblem::UnknownMethodDimension { method, dimension } => write!(
+                f,
+                "method {} uses dimension {} which is not found in root dimensions",
+                method, dimension
+            ),
+            ConfigProblem::UnknownResourceDef { context, resource_def } => write!(
+                f,
+                "cultural context {} has resource def {} which is not found in root resource defs",
+                context, resource_def
+            ),
+            ConfigProblem::DanglingThresholdDimension { context, dimension } => write!(
+                f,
+                "cultural context {} has a threshold on dimension {} which is not found in root dimensions",
+                context, dimension
+            ),
+            ConfigProblem::SubjectiveThresholdDimension { context, dimension } => write!(
+                f,
+                "cultural context {} has a threshold on subjective dimension {}, threshold dimensions must be objective",
+                context, dimension
+            ),
+            ConfigProblem::UnknownOrderByDimension { context, dimension } => write!(
+                f,
+                "cultural context {} orders by dimension {} which is not found in root dimensions",
+                context, dimension
+            ),
+            ConfigProblem::SubjectiveOrderByDimension { context, dimension } => write!(
+                f,
+                "cultural context {} orders by subjective dimension {}, order_by dimensions must be objective",
+                context, dimension
+            ),
+        }
+    }
+}
+
+fn duplicate_names<'a>(kind: &str, names: impl Iterator<Item = &'a String>) -> Vec<ConfigProblem> {
+    let mut seen = BTreeSet::new();
+    let mut duplicates = BTreeSet::new();
+    for name in names {
+        if !seen.insert(name) {
+            duplicates.insert(name);
+        }
+    }
+    duplicates
+        .into_iter()
+        .map(|name| ConfigProblem::DuplicateName {
+            kind: String::from(kind),
+            name: name.clone(),
+        })
+        .collect()
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug, SerializedBytes)]
 pub struct ConfigDimension {
     pub name: String,
     pub range: Range,
     pub computed: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub requires_validation: bool,
 }
 
 impl ConfigDimension {
-    pub fn check_format(self, range_ehs: Vec<EntryHash>) -> ExternResult<EntryHash> {
-        let converted_dimension: Dimension = Dimension::try_from(self.clone())?;
-        let dimension_range_eh = converted_dimension.clone().range_eh;
-
+    pub fn problems(&self, range_ehs: &[EntryHash]) -> ExternResult<Vec<ConfigProblem>> {
+        let mut problems = vec![];
         // check if range in dimension exists in the root ranges
-        if let false = range_ehs.contains(&dimension_range_eh) {
-            let error = format!(
-                "dimension name {} has range not found in root ranges",
-                self.name
-            );
-            return Err(wasm_error!(WasmErrorInner::Guest(error)));
-        }
-        Ok(hash_entry(converted_dimension)?)
+        if !range_ehs.contains(&hash_entry(self.range.clone())?) {
+            problems.push(ConfigProblem::UnknownRange {
+                dimension: self.name.clone(),
+            });
+        }
+        if self.requires_validation && !self.computed {
+            problems.push(ConfigProblem::SubjectiveValidatedDimension {
+                dimension: self.name.clone(),
+            });
+        }
+        Ok(problems)
     }
 }
 
@@ -106,55 +263,63 @@ pub struct ConfigMethod {
     pub name: String,
     pub input_dimensions: Vec<ConfigDimension>, // check if it's subjective (for now)
     pub output_dimension: ConfigDimension,      // check if it's objective
-    pub program: Program,                       // making enum for now, in design doc it is `AST`
+    pub program: Program,
     pub can_compute_live: bool,
     pub requires_validation: bool,
 }
 
 impl ConfigMethod {
-    pub fn check_format(self, root_dimension_ehs: Vec<EntryHash>) -> ExternResult<bool> {
-        let converted_method: Method = Method::try_from(self.clone())?;
-        let input_dimension_ehs = converted_method.input_dimension_ehs;
-        let output_dimension_eh = converted_method.output_dimension_eh;
+    pub fn problems(&self, root_dimension_ehs: &[EntryHash]) -> ExternResult<Vec<ConfigProblem>> {
+        let mut problems = vec![];
 
-        // check that the dimensions in input_dimensions are all subjective
-        // NOTE: in the future, we might want to also allow objective dimensions in the input dimensions.
-        if let false = self
+        // check that the program can be computed from the input ranges into the output range
+        let input_range_kinds = self
             .input_dimensions
-            .into_iter()
-            .all(|dimension| dimension.computed == false)
+            .iter()
+            .map(|dimension| dimension.range.kind.clone())
+            .collect::<Vec<RangeKind>>();
+        if let Err(error) = self
+            .program
+            .type_check(&input_range_kinds, &self.output_dimension.range.kind)
         {
-            let error = format!("method name {} has one or more input dimensions that are not a subjective dimension. All dimensions in input dimension must be subjective", self.name);
-            return Err(wasm_error!(WasmErrorInner::Guest(error)));
+            problems.push(ConfigProblem::InvalidProgram {
+                method: self.name.clone(),
+                error,
+            });
         }
 
-        // check that the dimension in output_dimension is objective
-        if self.output_dimension.computed != true {
-            let error = format!("method name {} has a subjective dimension defined in the output_dimension. output_dimension must be an objective dimension", self.name);
-            return Err(wasm_error!(WasmErrorInner::Guest(error)));
+        // NOTE: in the future, we might want to also allow objective dimensions in the input dimensions.
+        for dimension in self.input_dimensions.iter() {
+            if dimension.computed {
+                problems.push(ConfigProblem::ObjectiveInputDimension {
+                    method: self.name.clone(),
+                    dimension: dimension.name.clone(),
+                });
+            }
+        }
+        if !self.output_dimension.computed {
+            problems.push(ConfigProblem::SubjectiveOutputDimension {
+                method: self.name.clone(),
+                dimension: self.output_dimension.name.clone(),
+            });
+        }
+        if self.requires_validation != self.output_dimension.requires_validation {
+            problems.push(ConfigProblem::MethodValidationMismatch {
+                method: self.name.clone(),
+                dimension: self.output_dimension.name.clone(),
+            });
         }
 
-        // check if all dimensions in input dimensions exist in the root dimensions
-        if let false = input_dimension_ehs
-            .to_owned()
-            .into_iter()
-            .all(|eh| root_dimension_ehs.contains(&eh))
-        {
-            let error = format!(
-                "method name {} has one or more input dimensions not found in root dimensions",
-                self.name
-            );
-            return Err(wasm_error!(WasmErrorInner::Guest(error)));
-        }
-        // check if dimension in output dimensions exist in the root dimensions
-        if let false = root_dimension_ehs.contains(&output_dimension_eh) {
-            let error = format!(
-                "method name {} has an output dimension not found in root dimensions",
-                self.name
-            );
-            return Err(wasm_error!(WasmErrorInner::Guest(error)));
-        }
-        Ok(true)
+        // check if all input and output dimensions exist in the root dimensions
+        for dimension in self.input_dimensions.iter().chain([&self.output_dimension]) {
+            if !root_dimension_ehs.contains(&hash_entry(Dimension::try_from(dimension.clone())?)?) {
+                problems.push(ConfigProblem::UnknownMethodDimension {
+                    method: self.name.clone(),
+                    dimension: dimension.name.clone(),
+                });
+            }
+        }
+        Ok(problems)
     }
 }
 
@@ -164,51 +329,61 @@ pub struct ConfigCulturalContext {
     pub resource_def: ConfigResourceDef,
     pub thresholds: Vec<ConfigThreshold>,
     pub order_by: Vec<(ConfigDimension, OrderingKind)>, // DimensionEh
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threshold_condition: Option<ThresholdCondition<ConfigThreshold>>,
 }
 
 impl ConfigCulturalContext {
-    pub fn check_format(self, root_dimension_ehs: Vec<EntryHash>) -> ExternResult<bool> {
-        let converted_cc: CulturalContext = CulturalContext::try_from(self.to_owned())?;
-
-        let threholds_dimension_ehs = converted_cc
-            .thresholds
-            .into_iter()
-            .map(|th| th.dimension_eh)
-            .collect::<Vec<EntryHash>>();
-
-        let order_by_dimension_ehs = converted_cc
-            .order_by
-            .into_iter()
-            .map(|order| order.0)
-            .collect::<Vec<EntryHash>>();
-
-        // check that dimension in all thresholds exist in root dimensions
-        if let false = threholds_dimension_ehs
-            .into_iter()
-            .all(|eh| root_dimension_ehs.contains(&eh))
-        {
-            let error = format!("cultural context name {} has one or more threhold with dimension not found in root dimensions", self.name);
-            return Err(wasm_error!(WasmErrorInner::Guest(error)));
-        }
+    pub fn problems(
+        &self,
+        root_dimension_ehs: &[EntryHash],
+        root_resource_def_ehs: &[EntryHash],
+    ) -> ExternResult<Vec<ConfigProblem>> {
+        let mut problems = vec![];
 
-        // check that Dimension in order by exist root dimensions
-        if let false = order_by_dimension_ehs
-            .into_iter()
-            .all(|eh| root_dimension_ehs.contains(&eh))
-        {
-            let error = format!("cultural context name {} has one or more order_by with dimension not found in root dimensions", self.name);
-            return Err(wasm_error!(WasmErrorInner::Guest(error)));
+        if !root_resource_def_ehs.contains(&hash_entry(ResourceDef::try_from(self.resource_def.clone())?)?) {
+            problems.push(ConfigProblem::UnknownResourceDef {
+                context: self.name.clone(),
+                resource_def: self.resource_def.resource_name.clone(),
+            });
         }
 
-        // check that Dimension in order_by is objective
-        if let false = self.order_by.into_iter().all(|order_by| {
-            return order_by.0.computed == true;
-        }) {
-            let error = format!("cultural context name {} has one or more dimensions that are not an objective dimension in the order_by field. All dimensions in order_by must be objective", self.name);
-            return Err(wasm_error!(WasmErrorInner::Guest(error)));
+        // check that dimension in all thresholds exist in root dimensions and are objective
+        let mut thresholds: Vec<&ConfigThreshold> = self.thresholds.iter().collect();
+        if let Some(condition) = &self.threshold_condition {
+            thresholds.extend(condition.thresholds());
+        }
+        for threshold in thresholds {
+            if !root_dimension_ehs.contains(&hash_entry(Dimension::try_from(threshold.dimension.clone())?)?) {
+                problems.push(ConfigProblem::DanglingThresholdDimension {
+                    context: self.name.clone(),
+                    dimension: threshold.dimension.name.clone(),
+                });
+            }
+            if !threshold.dimension.computed {
+                problems.push(ConfigProblem::SubjectiveThresholdDimension {
+                    context: self.name.clone(),
+                    dimension: threshold.dimension.name.clone(),
+                });
+            }
         }
 
-        Ok(true)
+        // check that Dimensions in order by exist in root dimensions and are objective
+        for (dimension, _) in self.order_by.iter() {
+            if !root_dimension_ehs.contains(&hash_entry(Dimension::try_from(dimension.clone())?)?) {
+                problems.push(ConfigProblem::UnknownOrderByDimension {
+                    context: self.name.clone(),
+                    dimension: dimension.name.clone(),
+                });
+            }
+            if !dimension.computed {
+                problems.push(ConfigProblem::SubjectiveOrderByDimension {
+                    context: self.name.clone(),
+                    dimension: dimension.name.clone(),
+                });
+            }
+        }
+        Ok(problems)
     }
 }
 
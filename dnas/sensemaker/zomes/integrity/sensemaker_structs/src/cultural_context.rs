@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use hdi::prelude::*;
 
 use crate::{
@@ -11,8 +13,12 @@ use crate::{
 pub struct CulturalContext {
     pub name: String,
     pub resource_def_eh: EntryHash,
+    // every threshold must be met, along with the threshold condition if there is one
     pub thresholds: Vec<Threshold>,
     pub order_by: Vec<(EntryHash, OrderingKind)>, // DimensionEh
+    // skipped when empty so that contexts without a condition keep the same entry hash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threshold_condition: Option<ThresholdCondition>,
 }
 
 impl TryFrom<ConfigCulturalContext> for CulturalContext {
@@ -24,6 +30,10 @@ impl TryFrom<ConfigCulturalContext> for CulturalContext {
             .into_iter()
             .map(|th| Threshold::try_from(th))
             .collect::<ExternResult<Vec<Threshold>>>()?;
+        let threshold_condition = value
+            .threshold_condition
+            .map(|condition| condition.try_map(&Threshold::try_from))
+            .transpose()?;
         let mut order_by = vec![];
         value.order_by.into_iter().for_each(|item| {
             if let Ok(converted_dimension) = Dimension::try_from(item.0) {
@@ -46,6 +56,7 @@ impl TryFrom<ConfigCulturalContext> for CulturalContext {
             resource_def_eh: hash_entry(resource_def)?,
             thresholds,
             order_by,
+            threshold_condition,
         };
         Ok(cc)
     }
@@ -56,7 +67,8 @@ impl TryFrom<ConfigCulturalContext> for CulturalContext {
 pub struct ContextResult {
     pub context_eh: EntryHash,
     pub dimension_ehs: Vec<EntryHash>, // of objective dimensions
-    pub result: Vec<(EntryHash, Vec<RangeValue>)>,
+    // ordered resources with their latest value along each dimension, None where a resource has no assessment
+    pub result: Vec<(EntryHash, Vec<Option<RangeValue>>)>,
 }
 
 #[hdk_entry_helper]
@@ -90,4 +102,94 @@ pub enum ThresholdKind {
     GreaterThan,
     LessThan,
     Equal,
+    NotEqual,
+    GreaterOrEqual,
+    LessOrEqual,
+    // the threshold's value is the lower bound and this the upper bound, both inclusive
+    Between(RangeValue),
+    // met by the threshold's value or any of these
+    InSet(Vec<RangeValue>),
+}
+
+// a tree of thresholds, e.g. `Or([Threshold(score > 3), Threshold(pinned == 1)])`.
+// generic so that applet configs can use the same tree over `ConfigThreshold`s
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ThresholdCondition<T = Threshold> {
+    Threshold(T),
+    And(Vec<ThresholdCondition<T>>),
+    Or(Vec<ThresholdCondition<T>>),
+    Not(Box<ThresholdCondition<T>>),
+}
+
+impl<T> ThresholdCondition<T> {
+    pub fn thresholds(&self) -> Vec<&T> {
+        match self {
+            ThresholdCondition::Threshold(threshold) => vec![threshold],
+            ThresholdCondition::And(conditions) | ThresholdCondition::Or(conditions) => conditions
+                .iter()
+                .flat_map(|condition| condition.thresholds())
+                .collect(),
+            ThresholdCondition::Not(condition) => condition.thresholds(),
+        }
+    }
+
+    pub fn try_map<U>(
+        self,
+        f: &impl Fn(T) -> ExternResult<U>,
+    ) -> ExternResult<ThresholdCondition<U>> {
+        Ok(match self {
+            ThresholdCondition::Threshold(threshold) => ThresholdCondition::Threshold(f(threshold)?),
+            ThresholdCondition::And(conditions) => ThresholdCondition::And(
+                conditions
+                    .into_iter()
+                    .map(|condition| condition.try_map(f))
+                    .collect::<ExternResult<Vec<ThresholdCondition<U>>>>()?,
+            ),
+            ThresholdCondition::Or(conditions) => ThresholdCondition::Or(
+                conditions
+                    .into_iter()
+                    .map(|condition| condition.try_map(f))
+                    .collect::<ExternResult<Vec<ThresholdCondition<U>>>>()?,
+            ),
+            ThresholdCondition::Not(condition) => {
+                ThresholdCondition::Not(Box::new(condition.try_map(f)?))
+            }
+        })
+    }
+}
+
+impl ThresholdCondition {
+    // `latest_values` holds a resource's latest value on each dimension
+    pub fn is_met(&self, latest_values: &BTreeMap<EntryHash, RangeValue>) -> ExternResult<bool> {
+        match self {
+            ThresholdCondition::Threshold(threshold) => threshold.is_met(latest_values),
+            ThresholdCondition::And(conditions) => {
+                for condition in conditions {
+                    if !condition.is_met(latest_values)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            ThresholdCondition::Or(conditions) => {
+                for condition in conditions {
+                    if condition.is_met(latest_values)? {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+            ThresholdCondition::Not(condition) => Ok(!condition.is_met(latest_values)?),
+        }
+    }
+}
+
+impl Threshold {
+    // a threshold is checked against the resource's value on its own dimension, and is not met when there is none
+    pub fn is_met(&self, latest_values: &BTreeMap<EntryHash, RangeValue>) -> ExternResult<bool> {
+        match latest_values.get(&self.dimension_eh) {
+            Some(value) => value.meets_threshold(self.clone()),
+            None => Ok(false),
+        }
+    }
 }
@@ -10,6 +10,10 @@ pub struct Dimension {
     // identifies whether a dimension is objective or subjective
     // can be used to validate dimensions ehs being passed as io in method
     pub computed: bool,
+    // assessments along a computed dimension that requires validation need a dataset that validators
+    // recompute them from, so every method outputting to it must require validation too
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub requires_validation: bool,
 }
 
 impl TryFrom<ConfigDimension> for Dimension {
@@ -19,6 +23,7 @@ impl TryFrom<ConfigDimension> for Dimension {
             name: value.name,
             range_eh: hash_entry(value.range)?,
             computed: value.computed,
+            requires_validation: value.requires_validation,
         };
         Ok(dimension)
     }
@@ -3,19 +3,32 @@ mod assessment;
 mod cultural_context;
 mod dimension;
 mod method;
+mod program;
 mod properties;
 mod range;
 mod resource_def;
+mod role;
+mod succession;
 mod assessment_control;
 
-pub use applet::{AppletConfig, AppletConfigInput, ConfigResourceDef};
+pub use applet::{
+    first_applet_config_version, AppletConfig, AppletConfigInput, ConfigProblem, ConfigResourceDef,
+};
 pub use assessment::Assessment;
 pub use cultural_context::{
-    ContextResult, CulturalContext, OrderingKind, Threshold, ThresholdKind,
+    ContextResult, CulturalContext, OrderingKind, Threshold, ThresholdCondition, ThresholdKind,
 };
 pub use dimension::Dimension;
-pub use method::{DataSet, Method, PartialMethod, Program};
+pub use method::{DataSet, Method, PartialMethod};
+pub use program::{Comparison, Condition, Expression, Program};
 pub use properties::{Properties, SensemakerConfig};
-pub use range::{Range, RangeValue};
+pub use range::{Range, RangeKind, RangeValue};
 pub use resource_def::ResourceDef;
+pub use role::{
+    has_role, has_unrevoked_role, is_community_activator_at, Role, RoleCheckpoint, RoleClaim, RoleGrant,
+    RoleRevocation,
+};
+pub use succession::{
+    CommunityActivatorClaim, CommunityActivatorHandover, HandoverApproval, StewardApproval,
+};
 pub use assessment_control::{AssessmentControlRegistration, AssessmentControlRegistrationInput};
@@ -1,8 +1,6 @@
-use std::collections::BTreeMap;
-
 use hdi::prelude::*;
 
-use crate::{applet::ConfigMethod, Dimension};
+use crate::{applet::ConfigMethod, Dimension, Program};
 
 #[hdk_entry_helper]
 #[derive(Clone)]
@@ -10,7 +8,7 @@ pub struct Method {
     pub name: String,
     pub input_dimension_ehs: Vec<EntryHash>, // Validation: make sure it is subjective
     pub output_dimension_eh: EntryHash,      // Validation: make sure it is objective
-    pub program: Program,                    // making enum for now, in design doc it is `AST`
+    pub program: Program,                    // see `Program::type_check` for the input/output validation
     pub can_compute_live: bool,
     pub requires_validation: bool, // if true, DataSet must be committed to be retrievable in the validation The Objective Assesment must have the DataSet.
 }
@@ -57,11 +55,7 @@ impl TryFrom<ConfigMethod> for Method {
 #[derive(Clone)]
 pub struct DataSet {
     pub from: EntryHash,                                  // method
-    pub data_points: BTreeMap<EntryHash, Vec<EntryHash>>, //<DimensionEh, Vec<AssessmentEh>>
-}
-
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub enum Program {
-    Sum,
-    Average,
+    // (DimensionEh, Vec<AssessmentEh>) in the order of the method's input dimensions.
+    // not a map, as maps with hash keys can't be decoded by the client
+    pub data_points: Vec<(EntryHash, Vec<EntryHash>)>,
 }
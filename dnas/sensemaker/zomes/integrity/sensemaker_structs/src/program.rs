@@ -0,0 +1,575 @@
+use hdi::prelude::*;
+use std::cmp::Ordering;
+
+use crate::{range::compare_floats, RangeKind, RangeValue};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum Program {
+    Sum,
+    Average,
+    // number of input assessments with exactly this value, e.g. how many times a post was tagged "spam"
+    Count(RangeValue),
+    // most frequently assessed value, ties resolve to the smallest value
+    Mode,
+    Expression(Expression),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum Expression {
+    Constant(RangeValue),
+    // all values assessed along the input dimension at this position in `input_dimension_ehs`
+    Dimension(u32),
+    // aggregates, taking a list of values and producing a single one
+    Count(Box<Expression>),
+    Sum(Box<Expression>),
+    Average(Box<Expression>),
+    Min(Box<Expression>),
+    Max(Box<Expression>),
+    Median(Box<Expression>),
+    // arithmetic on single numeric values. Integer operands give an Integer, otherwise a Float
+    Add(Box<Expression>, Box<Expression>),
+    Subtract(Box<Expression>, Box<Expression>),
+    Multiply(Box<Expression>, Box<Expression>),
+    Divide(Box<Expression>, Box<Expression>),
+    // sum of each numeric value multiplied by its weight, always a Float
+    Weighted(Vec<(f64, Expression)>),
+    If {
+        condition: Box<Condition>,
+        then: Box<Expression>,
+        otherwise: Box<Expression>,
+    },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum Condition {
+    Compare(Box<Expression>, Comparison, Box<Expression>),
+    And(Vec<Condition>),
+    Or(Vec<Condition>),
+    Not(Box<Condition>),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum Comparison {
+    GreaterThan,
+    LessThan,
+    Equal,
+    NotEqual,
+}
+
+// the kind of value a program produces, checked against the kind of the output dimension's range
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ValueKind {
+    Integer,
+    Float,
+    Tag,
+    Emoji,
+    TagTree,
+}
+
+impl ValueKind {
+    fn is_numeric(&self) -> bool {
+        matches!(self, ValueKind::Integer | ValueKind::Float)
+    }
+}
+
+impl From<&RangeKind> for ValueKind {
+    fn from(kind: &RangeKind) -> Self {
+        match kind {
+            RangeKind::Integer { .. } => ValueKind::Integer,
+            RangeKind::Float { .. } => ValueKind::Float,
+            RangeKind::Tag(_) => ValueKind::Tag,
+            RangeKind::Emoji(_) => ValueKind::Emoji,
+            RangeKind::TagTree(_) => ValueKind::TagTree,
+        }
+    }
+}
+
+impl From<&RangeValue> for ValueKind {
+    fn from(value: &RangeValue) -> Self {
+        match value {
+            RangeValue::Integer(_) => ValueKind::Integer,
+            RangeValue::Float(_) => ValueKind::Float,
+            RangeValue::Tag(_) => ValueKind::Tag,
+            RangeValue::Emoji(_) => ValueKind::Emoji,
+            RangeValue::TagTree(_) => ValueKind::TagTree,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExpressionType {
+    Value(ValueKind),
+    List(ValueKind),
+}
+
+enum Evaluated {
+    Value(RangeValue),
+    List(Vec<RangeValue>),
+}
+
+impl Program {
+    // static check that the program can run on values of the input ranges and
+    // produces values of the output range's kind
+    pub fn type_check(&self, input_kinds: &[RangeKind], output_kind: &RangeKind) -> Result<(), String> {
+        let input_kinds: Vec<ValueKind> = input_kinds.iter().map(ValueKind::from).collect();
+        let output_kind = ValueKind::from(output_kind);
+        let program_kind = match self {
+            Program::Sum | Program::Average => {
+                if !input_kinds.iter().all(|kind| kind.is_numeric()) {
+                    return Err(String::from(
+                        "Sum and Average can only be computed over Integer and Float dimensions",
+                    ));
+                }
+                if input_kinds.iter().all(|kind| *kind == ValueKind::Integer) {
+                    ValueKind::Integer
+                } else {
+                    ValueKind::Float
+                }
+            }
+            Program::Count(counted_value) => {
+                let counted_kind = ValueKind::from(counted_value);
+                if !input_kinds.iter().any(|kind| *kind == counted_kind) {
+                    return Err(format!(
+                        "no input dimension can have {:?} values to count",
+                        counted_kind
+                    ));
+                }
+                ValueKind::Integer
+            }
+            Program::Mode => match input_kinds.first() {
+                Some(first_kind) if input_kinds.iter().all(|kind| kind == first_kind) => *first_kind,
+                Some(_) => {
+                    return Err(String::from(
+                        "Mode can only be computed over input dimensions of the same kind",
+                    ))
+                }
+                None => return Err(String::from("Mode needs at least one input dimension")),
+            },
+            Program::Expression(expression) => match expression.type_check(&input_kinds)? {
+                ExpressionType::Value(kind) => kind,
+                ExpressionType::List(_) => {
+                    return Err(String::from(
+                        "expression evaluates to a list of values, aggregate it into a single value",
+                    ))
+                }
+            },
+        };
+        if program_kind != output_kind {
+            return Err(format!(
+                "program produces {:?} values but the output dimension's range is {:?}",
+                program_kind, output_kind
+            ));
+        }
+        Ok(())
+    }
+
+    // `inputs` holds the assessed values along each input dimension, in the order of `input_dimension_ehs`,
+    // and the value is of the kind of the output range the program was type checked against
+    pub fn compute(&self, inputs: Vec<Vec<RangeValue>>, output_kind: &RangeKind) -> ExternResult<RangeValue> {
+        into_kind(self.compute_value(inputs)?, ValueKind::from(output_kind))
+    }
+
+    fn compute_value(&self, inputs: Vec<Vec<RangeValue>>) -> ExternResult<RangeValue> {
+        match self {
+            Program::Sum => sum(inputs.into_iter().flatten().collect()),
+            Program::Average => average(inputs.into_iter().flatten().collect()),
+            Program::Count(counted_value) => {
+                let count = inputs
+                    .into_iter()
+                    .flatten()
+                    .filter(|value| value == counted_value)
+                    .count();
+                Ok(RangeValue::Integer(count as u32))
+            }
+            Program::Mode => {
+                // RangeValue is neither Ord nor Hash (it can hold floats), so tally in a vec
+                let mut tally: Vec<(RangeValue, u32)> = Vec::new();
+                for value in inputs.into_iter().flatten() {
+                    if let Some(entry) = tally.iter_mut().find(|(tallied, _)| *tallied == value) {
+                        entry.1 += 1;
+                    } else {
+                        tally.push((value, 1));
+                    }
+                }
+                let mut maybe_mode: Option<(RangeValue, u32)> = None;
+                for (value, count) in tally {
+                    let is_new_mode = match &maybe_mode {
+                        None => true,
+                        Some((mode_value, mode_count)) => {
+                            count > *mode_count
+                                || (count == *mode_count
+                                    && order_values(&value, mode_value)? == Ordering::Less)
+                        }
+                    };
+                    if is_new_mode {
+                        maybe_mode = Some((value, count));
+                    }
+                }
+                maybe_mode
+                    .map(|(value, _)| value)
+                    .ok_or_else(|| guest_error("no assessments to compute the mode of"))
+            }
+            Program::Expression(expression) => match expression.evaluate(&inputs)? {
+                Evaluated::Value(value) => Ok(value),
+                Evaluated::List(_) => Err(guest_error(
+                    "expression evaluated to a list of values instead of a single value",
+                )),
+            },
+        }
+    }
+}
+
+impl Expression {
+    pub fn type_check(&self, input_kinds: &[ValueKind]) -> Result<ExpressionType, String> {
+        match self {
+            Expression::Constant(value) => Ok(ExpressionType::Value(ValueKind::from(value))),
+            Expression::Dimension(index) => input_kinds
+                .get(*index as usize)
+                .map(|kind| ExpressionType::List(*kind))
+                .ok_or_else(|| {
+                    format!(
+                        "expression refers to input dimension {} but the method only has {}",
+                        index,
+                        input_kinds.len()
+                    )
+                }),
+            Expression::Count(list) => match list.type_check(input_kinds)? {
+                ExpressionType::List(_) => Ok(ExpressionType::Value(ValueKind::Integer)),
+                ExpressionType::Value(_) => Err(String::from("Count needs a list of values")),
+            },
+            Expression::Sum(list)
+            | Expression::Average(list)
+            | Expression::Min(list)
+            | Expression::Max(list)
+            | Expression::Median(list) => match list.type_check(input_kinds)? {
+                ExpressionType::List(kind) if kind.is_numeric() => Ok(ExpressionType::Value(kind)),
+                _ => Err(String::from(
+                    "Sum, Average, Min, Max and Median need a list of Integer or Float values",
+                )),
+            },
+            Expression::Add(left, right)
+            | Expression::Subtract(left, right)
+            | Expression::Multiply(left, right)
+            | Expression::Divide(left, right) => {
+                match (left.type_check(input_kinds)?, right.type_check(input_kinds)?) {
+                    (ExpressionType::Value(ValueKind::Integer), ExpressionType::Value(ValueKind::Integer)) => {
+                        Ok(ExpressionType::Value(ValueKind::Integer))
+                    }
+                    (ExpressionType::Value(left_kind), ExpressionType::Value(right_kind))
+                        if left_kind.is_numeric() && right_kind.is_numeric() =>
+                    {
+                        Ok(ExpressionType::Value(ValueKind::Float))
+                    }
+                    _ => Err(String::from(
+                        "arithmetic needs single Integer or Float values on both sides",
+                    )),
+                }
+            }
+            Expression::Weighted(terms) => {
+                if terms.is_empty() {
+                    return Err(String::from("Weighted needs at least one term"));
+                }
+                for (_, term) in terms {
+                    match term.type_check(input_kinds)? {
+                        ExpressionType::Value(kind) if kind.is_numeric() => {}
+                        _ => return Err(String::from("Weighted terms must be single Integer or Float values")),
+                    }
+                }
+                Ok(ExpressionType::Value(ValueKind::Float))
+            }
+            Expression::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                condition.type_check(input_kinds)?;
+                let then_type = then.type_check(input_kinds)?;
+                let otherwise_type = otherwise.type_check(input_kinds)?;
+                if then_type != otherwise_type {
+                    return Err(format!(
+                        "both branches of If must have the same type, found {:?} and {:?}",
+                        then_type, otherwise_type
+                    ));
+                }
+                Ok(then_type)
+            }
+        }
+    }
+
+    fn evaluate(&self, inputs: &[Vec<RangeValue>]) -> ExternResult<Evaluated> {
+        match self {
+            Expression::Constant(value) => Ok(Evaluated::Value(value.clone())),
+            Expression::Dimension(index) => inputs
+                .get(*index as usize)
+                .map(|values| Evaluated::List(values.clone()))
+                .ok_or_else(|| guest_error("expression refers to a missing input dimension")),
+            Expression::Count(list) => {
+                let count = list.evaluate_list(inputs)?.len();
+                Ok(Evaluated::Value(RangeValue::Integer(count as u32)))
+            }
+            Expression::Sum(list) => Ok(Evaluated::Value(sum(list.evaluate_list(inputs)?)?)),
+            Expression::Average(list) => Ok(Evaluated::Value(average(list.evaluate_list(inputs)?)?)),
+            Expression::Min(list) => {
+                let values = sort_numeric(list.evaluate_list(inputs)?)?;
+                Ok(Evaluated::Value(
+                    values
+                        .into_iter()
+                        .next()
+                        .ok_or_else(|| guest_error("cannot take the minimum of no assessments"))?,
+                ))
+            }
+            Expression::Max(list) => {
+                let values = sort_numeric(list.evaluate_list(inputs)?)?;
+                Ok(Evaluated::Value(
+                    values
+                        .into_iter()
+                        .last()
+                        .ok_or_else(|| guest_error("cannot take the maximum of no assessments"))?,
+                ))
+            }
+            Expression::Median(list) => {
+                let values = sort_numeric(list.evaluate_list(inputs)?)?;
+                if values.is_empty() {
+                    return Err(guest_error("cannot take the median of no assessments"));
+                }
+                let middle = values.len() / 2;
+                if values.len() % 2 == 1 {
+                    Ok(Evaluated::Value(values[middle].clone()))
+                } else {
+                    Ok(Evaluated::Value(average(vec![
+                        values[middle - 1].clone(),
+                        values[middle].clone(),
+                    ])?))
+                }
+            }
+            Expression::Add(left, right) => Ok(Evaluated::Value(arithmetic(
+                left.evaluate_value(inputs)?,
+                right.evaluate_value(inputs)?,
+                u32::checked_add,
+                |left, right| left + right,
+            )?)),
+            Expression::Subtract(left, right) => Ok(Evaluated::Value(arithmetic(
+                left.evaluate_value(inputs)?,
+                right.evaluate_value(inputs)?,
+                u32::checked_sub,
+                |left, right| left - right,
+            )?)),
+            Expression::Multiply(left, right) => Ok(Evaluated::Value(arithmetic(
+                left.evaluate_value(inputs)?,
+                right.evaluate_value(inputs)?,
+                u32::checked_mul,
+                |left, right| left * right,
+            )?)),
+            Expression::Divide(left, right) => {
+                let right_value = right.evaluate_value(inputs)?;
+                if as_float(&right_value)? == 0.0 {
+                    return Err(guest_error("division by zero"));
+                }
+                Ok(Evaluated::Value(arithmetic(
+                    left.evaluate_value(inputs)?,
+                    right_value,
+                    u32::checked_div,
+                    |left, right| left / right,
+                )?))
+            }
+            Expression::Weighted(terms) => {
+                let mut total = 0.0;
+                for (weight, term) in terms {
+                    total += weight * as_float(&term.evaluate_value(inputs)?)?;
+                }
+                Ok(Evaluated::Value(finite(total)?))
+            }
+            Expression::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                if condition.evaluate(inputs)? {
+                    then.evaluate(inputs)
+                } else {
+                    otherwise.evaluate(inputs)
+                }
+            }
+        }
+    }
+
+    fn evaluate_value(&self, inputs: &[Vec<RangeValue>]) -> ExternResult<RangeValue> {
+        match self.evaluate(inputs)? {
+            Evaluated::Value(value) => Ok(value),
+            Evaluated::List(_) => Err(guest_error("expected a single value but found a list")),
+        }
+    }
+
+    fn evaluate_list(&self, inputs: &[Vec<RangeValue>]) -> ExternResult<Vec<RangeValue>> {
+        match self.evaluate(inputs)? {
+            Evaluated::List(values) => Ok(values),
+            Evaluated::Value(_) => Err(guest_error("expected a list but found a single value")),
+        }
+    }
+}
+
+impl Condition {
+    fn type_check(&self, input_kinds: &[ValueKind]) -> Result<(), String> {
+        match self {
+            Condition::Compare(left, comparison, right) => {
+                match (left.type_check(input_kinds)?, right.type_check(input_kinds)?) {
+                    (ExpressionType::Value(left_kind), ExpressionType::Value(right_kind))
+                        if left_kind.is_numeric() && right_kind.is_numeric() =>
+                    {
+                        Ok(())
+                    }
+                    (ExpressionType::Value(left_kind), ExpressionType::Value(right_kind))
+                        if left_kind == right_kind =>
+                    {
+                        match comparison {
+                            Comparison::Equal | Comparison::NotEqual => Ok(()),
+                            _ => Err(String::from(
+                                "categorical values can only be compared with Equal or NotEqual",
+                            )),
+                        }
+                    }
+                    _ => Err(String::from(
+                        "conditions compare single values of compatible kinds",
+                    )),
+                }
+            }
+            Condition::And(conditions) | Condition::Or(conditions) => conditions
+                .iter()
+                .map(|condition| condition.type_check(input_kinds))
+                .collect(),
+            Condition::Not(condition) => condition.type_check(input_kinds),
+        }
+    }
+
+    fn evaluate(&self, inputs: &[Vec<RangeValue>]) -> ExternResult<bool> {
+        match self {
+            Condition::Compare(left, comparison, right) => {
+                let left_value = left.evaluate_value(inputs)?;
+                let right_value = right.evaluate_value(inputs)?;
+                let ordering = order_values(&left_value, &right_value)?;
+                Ok(match comparison {
+                    Comparison::GreaterThan => ordering == Ordering::Greater,
+                    Comparison::LessThan => ordering == Ordering::Less,
+                    Comparison::Equal => ordering == Ordering::Equal,
+                    Comparison::NotEqual => ordering != Ordering::Equal,
+                })
+            }
+            Condition::And(conditions) => {
+                for condition in conditions {
+                    if !condition.evaluate(inputs)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            Condition::Or(conditions) => {
+                for condition in conditions {
+                    if condition.evaluate(inputs)? {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+            Condition::Not(condition) => Ok(!condition.evaluate(inputs)?),
+        }
+    }
+}
+
+fn guest_error(message: &str) -> WasmError {
+    wasm_error!(WasmErrorInner::Guest(String::from(message)))
+}
+
+fn as_float(value: &RangeValue) -> ExternResult<f64> {
+    match value {
+        RangeValue::Integer(value) => Ok(*value as f64),
+        RangeValue::Float(value) => Ok(*value),
+        _ => Err(guest_error("expected an Integer or Float value")),
+    }
+}
+
+fn finite(value: f64) -> ExternResult<RangeValue> {
+    if value.is_finite() {
+        Ok(RangeValue::Float(value))
+    } else {
+        Err(guest_error("computation produced a float that is not finite"))
+    }
+}
+
+// numbers are compared by value regardless of Integer/Float, everything else falls back to `RangeValue::compare`
+fn order_values(left: &RangeValue, right: &RangeValue) -> ExternResult<Ordering> {
+    match (left, right) {
+        (RangeValue::Integer(left), RangeValue::Integer(right)) => Ok(left.cmp(right)),
+        (RangeValue::Integer(_) | RangeValue::Float(_), RangeValue::Integer(_) | RangeValue::Float(_)) => {
+            Ok(compare_floats(as_float(left)?, as_float(right)?))
+        }
+        _ => left.compare(right.clone()),
+    }
+}
+
+// values are sorted before aggregating so that float results don't depend on the order links were returned in
+fn sort_numeric(mut values: Vec<RangeValue>) -> ExternResult<Vec<RangeValue>> {
+    for value in values.iter() {
+        as_float(value)?;
+    }
+    values.sort_by(|left, right| order_values(left, right).unwrap_or(Ordering::Equal));
+    Ok(values)
+}
+
+fn sum(values: Vec<RangeValue>) -> ExternResult<RangeValue> {
+    let values = sort_numeric(values)?;
+    if values.iter().all(|value| matches!(value, RangeValue::Integer(_))) {
+        let mut total: u32 = 0;
+        for value in values {
+            if let RangeValue::Integer(value) = value {
+                total = total
+                    .checked_add(value)
+                    .ok_or_else(|| guest_error("integer sum overflowed"))?;
+            }
+        }
+        Ok(RangeValue::Integer(total))
+    } else {
+        let mut total = 0.0;
+        for value in values.iter() {
+            total += as_float(value)?;
+        }
+        finite(total)
+    }
+}
+
+fn average(values: Vec<RangeValue>) -> ExternResult<RangeValue> {
+    if values.is_empty() {
+        return Err(guest_error("cannot average no assessments"));
+    }
+    let count = values.len();
+    match sum(values)? {
+        RangeValue::Integer(total) => Ok(RangeValue::Integer(total / count as u32)),
+        total => finite(as_float(&total)? / count as f64),
+    }
+}
+
+// aggregates take their kind from the values present, so a Sum over no values or over only the Integer ones
+// of mixed input dimensions is an Integer where the type check expects a Float
+fn into_kind(value: RangeValue, kind: ValueKind) -> ExternResult<RangeValue> {
+    match (value, kind) {
+        (RangeValue::Integer(value), ValueKind::Float) => Ok(RangeValue::Float(value as f64)),
+        (value, kind) if ValueKind::from(&value) == kind => Ok(value),
+        (value, kind) => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "program produced {:?} where a {:?} value was expected",
+            value, kind
+        )))),
+    }
+}
+
+fn arithmetic(
+    left: RangeValue,
+    right: RangeValue,
+    integer_op: fn(u32, u32) -> Option<u32>,
+    float_op: fn(f64, f64) -> f64,
+) -> ExternResult<RangeValue> {
+    match (&left, &right) {
+        (RangeValue::Integer(left), RangeValue::Integer(right)) => integer_op(*left, *right)
+            .map(RangeValue::Integer)
+            .ok_or_else(|| guest_error("integer arithmetic went out of bounds")),
+        _ => finite(float_op(as_float(&left)?, as_float(&right)?)),
+    }
+}
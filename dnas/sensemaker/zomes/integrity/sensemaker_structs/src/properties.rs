@@ -25,4 +25,39 @@ pub struct SensemakerConfig {
     pub neighbourhood: String,
     pub community_activator: AgentPubKeyB64,
     pub wizard_version: String,
+    // number of stewards that can together hand the community activator role over to a new agent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub steward_quorum: Option<u32>,
 }
+
+impl SensemakerConfig {
+    pub fn check_format(&self) -> Result<(), String> {
+        if self.neighbourhood.trim().is_empty() {
+            return Err(String::from("neighbourhood can't be blank"));
+        }
+        if self.neighbourhood.chars().count() > MAX_NEIGHBOURHOOD_NAME_LENGTH {
+            return Err(format!(
+                "neighbourhood can be at most {} characters long",
+                MAX_NEIGHBOURHOOD_NAME_LENGTH
+            ));
+        }
+        if self.wizard_version_numbers().is_none() {
+            return Err(format!(
+                "wizard_version {} should be a \"v\" followed by dot separated numbers, e.g. v0.1",
+                self.wizard_version
+            ));
+        }
+        Ok(())
+    }
+
+    // "v0.1" is [0, 1], so versions compare numerically
+    pub fn wizard_version_numbers(&self) -> Option<Vec<u32>> {
+        let numbers = self.wizard_version.strip_prefix('v')?;
+        numbers
+            .split('.')
+            .map(|number| number.parse::<u32>().ok())
+            .collect()
+    }
+}
+
+const MAX_NEIGHBOURHOOD_NAME_LENGTH: usize = 100;
@@ -1,6 +1,7 @@
 use crate::{Threshold, ThresholdKind};
 use hdi::prelude::*;
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
 
 #[hdk_entry_helper]
 #[derive(Clone)]
@@ -14,74 +15,163 @@ pub struct Range {
 pub enum RangeKind {
     Integer { min: u32, max: u32 },
     Float { min: f64, max: f64 },
-    // Tag(Vec<String>),
-    // Emoji(Vec<char>),
-    // TagTree(HashMap<String, String>)
+    Tag(Vec<String>),
+    Emoji(Vec<char>),
+    // branch -> leaf tags. BTreeMap so that the serialized range (and its entry hash) is deterministic
+    TagTree(BTreeMap<String, Vec<String>>),
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub enum RangeValue {
     Integer(u32),
     Float(f64),
-    // Tag(String),
-    // Emoji(char),
-    // TagTree((String, String))
+    Tag(String),
+    Emoji(char),
+    TagTree((String, String)), // (branch, leaf)
 }
 
-impl RangeValue {
-    pub fn meets_threshold(&self, threshold: Threshold) -> ExternResult<bool> {
-        // check that same variant type
+impl RangeKind {
+    // numeric ranges need min <= max, so that some value can fall within them
+    pub fn check_bounds(&self) -> Result<(), String> {
         match self {
-            RangeValue::Integer(self_value) => {
-                let other_range_value = threshold.value;
-                if let RangeValue::Integer(other_value) = other_range_value {
-                    match threshold.kind {
-                        ThresholdKind::GreaterThan => Ok(*self_value > other_value),
-                        ThresholdKind::LessThan => Ok(*self_value < other_value),
-                        ThresholdKind::Equal => Ok(*self_value == other_value),
-                    }
+            RangeKind::Integer { min, max } if min > max => {
+                Err(format!("min {} is greater than max {}", min, max))
+            }
+            RangeKind::Float { min, max } if !(min <= max) => {
+                Err(format!("min {} is not less than or equal to max {}", min, max))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // checks that a value is of the same variant as the range and falls within it
+    pub fn check_value(&self, value: &RangeValue) -> Result<(), String> {
+        match (self, value) {
+            (RangeKind::Integer { min, max }, RangeValue::Integer(value)) => {
+                if value < min || value > max {
+                    Err(format!("value {} is outside of the range {} to {}", value, min, max))
+                } else {
+                    Ok(())
                 }
-                // could put `if else` here for compatible range types that are not the same
-                else {
-                    Err(wasm_error!(WasmErrorInner::Guest(String::from(
-                        "incompatible range types for threshold comparison"
-                    ))))
+            }
+            (RangeKind::Float { min, max }, RangeValue::Float(value)) => {
+                if value.is_nan() {
+                    Err(String::from("NaN is not a valid value"))
+                } else if !(value >= min && value <= max) {
+                    Err(format!("value {} is outside of the range {} to {}", value, min, max))
+                } else {
+                    Ok(())
                 }
             }
-            RangeValue::Float(self_value) => {
-                let other_range_value = threshold.value;
-                if let RangeValue::Float(other_value) = other_range_value {
-                    match threshold.kind {
-                        ThresholdKind::GreaterThan => Ok(*self_value > other_value),
-                        ThresholdKind::LessThan => Ok(*self_value < other_value),
-                        ThresholdKind::Equal => Ok(*self_value == other_value),
-                    }
+            (RangeKind::Tag(tags), RangeValue::Tag(tag)) => {
+                if tags.contains(tag) {
+                    Ok(())
+                } else {
+                    Err(format!("tag {} is not one of the tags in the range", tag))
                 }
-                // could put `if else` here for compatible range types that are not the same
-                else {
-                    Err(wasm_error!(WasmErrorInner::Guest(String::from(
-                        "incompatible range types for threshold comparison"
-                    ))))
+            }
+            (RangeKind::Emoji(emojis), RangeValue::Emoji(emoji)) => {
+                if emojis.contains(emoji) {
+                    Ok(())
+                } else {
+                    Err(format!("emoji {} is not one of the emojis in the range", emoji))
+                }
+            }
+            (RangeKind::TagTree(tree), RangeValue::TagTree((branch, leaf))) => {
+                if tree.get(branch).map_or(false, |leaves| leaves.contains(leaf)) {
+                    Ok(())
+                } else {
+                    Err(format!("tag {} under branch {} is not in the range", leaf, branch))
+                }
+            }
+            _ => Err(String::from("value is not of the same kind as the range")),
+        }
+    }
+}
+
+impl RangeValue {
+    pub fn meets_threshold(&self, threshold: Threshold) -> ExternResult<bool> {
+        match threshold.kind {
+            ThresholdKind::GreaterThan => Ok(self.numeric_ordering(threshold.value)? == Ordering::Greater),
+            ThresholdKind::LessThan => Ok(self.numeric_ordering(threshold.value)? == Ordering::Less),
+            ThresholdKind::GreaterOrEqual => Ok(self.numeric_ordering(threshold.value)? != Ordering::Less),
+            ThresholdKind::LessOrEqual => Ok(self.numeric_ordering(threshold.value)? != Ordering::Greater),
+            ThresholdKind::Between(upper_value) => Ok(self.numeric_ordering(threshold.value)?
+                != Ordering::Less
+                && self.numeric_ordering(upper_value)? != Ordering::Greater),
+            ThresholdKind::Equal => self.matches(threshold.value),
+            ThresholdKind::NotEqual => Ok(!self.matches(threshold.value)?),
+            ThresholdKind::InSet(other_values) => {
+                for other_value in std::iter::once(threshold.value).chain(other_values) {
+                    if self.matches(other_value)? {
+                        return Ok(true);
+                    }
                 }
+                Ok(false)
+            }
+        }
+    }
 
-            },
+    // equality for thresholds. a tag tree value matches a tag threshold when it sits under that branch
+    fn matches(&self, other_range_value: RangeValue) -> ExternResult<bool> {
+        match (self, other_range_value) {
+            (RangeValue::TagTree((branch, _)), RangeValue::Tag(other_branch)) => {
+                Ok(*branch == other_branch)
+            }
+            (_, other_range_value) => Ok(self.compare(other_range_value)? == Ordering::Equal),
         }
     }
 
-    pub fn compare(&self, other_range_value: RangeValue) -> ExternResult<Ordering> {
+    // categorical values are only ordered to keep orderings deterministic, so they can't be used for
+    // greater/less than thresholds
+    fn numeric_ordering(&self, other_range_value: RangeValue) -> ExternResult<Ordering> {
         match self {
-            RangeValue::Integer(self_value) => {
-                if let RangeValue::Integer(other_value) = other_range_value {
-                    Ok(self_value.cmp(&other_value))
-                }
-                // could put `if else` here for compatible range types that are not the same
-                else {
-                    Err(wasm_error!(WasmErrorInner::Guest(String::from(
-                        "incompatible range types for comparison"
-                    ))))
-                }
+            RangeValue::Integer(_) | RangeValue::Float(_) => self.compare(other_range_value),
+            _ => Err(wasm_error!(WasmErrorInner::Guest(String::from(
+                "categorical range values can only be compared with Equal, NotEqual or InSet thresholds"
+            )))),
+        }
+    }
+
+    // categorical values have no natural order, so they are ordered lexicographically to keep
+    // any ordering over them deterministic across agents.
+    // `self` is the value on the dimension being compared, `other_range_value` may be coerced to its kind
+    pub fn compare(&self, other_range_value: RangeValue) -> ExternResult<Ordering> {
+        match (self, other_range_value) {
+            (RangeValue::Integer(self_value), RangeValue::Integer(other_value)) => {
+                Ok(self_value.cmp(&other_value))
+            }
+            (RangeValue::Float(self_value), RangeValue::Float(other_value)) => {
+                Ok(compare_floats(*self_value, other_value))
+            }
+            // a Float dimension can take Integer values for comparison as they widen to floats without loss.
+            // the other way round is rejected, as an Integer dimension can't represent the float
+            (RangeValue::Float(self_value), RangeValue::Integer(other_value)) => {
+                Ok(compare_floats(*self_value, other_value as f64))
+            }
+            (RangeValue::Tag(self_value), RangeValue::Tag(other_value)) => {
+                Ok(self_value.cmp(&other_value))
             }
-            RangeValue::Float(_) => Ok(Ordering::Equal), // TODO: fix this along with other range value types
+            (RangeValue::Emoji(self_value), RangeValue::Emoji(other_value)) => {
+                Ok(self_value.cmp(&other_value))
+            }
+            (RangeValue::TagTree(self_value), RangeValue::TagTree(other_value)) => {
+                Ok(self_value.cmp(&other_value))
+            }
+            // could put more arms here for compatible range types that are not the same
+            _ => Err(wasm_error!(WasmErrorInner::Guest(String::from(
+                "incompatible range types for comparison"
+            )))),
         }
     }
 }
+
+// a total order over floats: -0.0 and 0.0 are equal, infinities sit at either end and NaN sorts
+// after every other value. NaN is rejected by `RangeKind::check_value`, but could still come from
+// assessments committed before that validation existed
+pub fn compare_floats(value: f64, other_value: f64) -> Ordering {
+    match value.partial_cmp(&other_value) {
+        Some(ordering) => ordering,
+        None => value.is_nan().cmp(&other_value.is_nan()),
+    }
+}
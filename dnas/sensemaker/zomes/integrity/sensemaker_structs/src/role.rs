@@ -0,0 +1,243 @@
+use std::collections::BTreeSet;
+
+use hdi::prelude::*;
+
+use crate::succession::{community_activator_handover_in, CommunityActivatorClaim, CommunityActivatorHandover};
+
+// how long a checkpoint keeps the agent's roles after the community activator's action it pins
+const ROLE_CHECKPOINT_LEASE_MICROS: i64 = 30 * 24 * 60 * 60 * 1_000_000;
+
+// roles the community activator can grant to other agents
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Role {
+    // can do everything the other roles can
+    Steward,
+    MethodAuthor,
+    TrayCurator,
+}
+
+impl Role {
+    pub fn includes(&self, role: &Role) -> bool {
+        *self == Role::Steward || self == role
+    }
+}
+
+impl std::fmt::Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Role::Steward => write!(f, "steward"),
+            Role::MethodAuthor => write!(f, "method author"),
+            Role::TrayCurator => write!(f, "tray curator"),
+        }
+    }
+}
+
+// authored by the community activator
+#[hdk_entry_helper]
+#[derive(Clone)]
+pub struct RoleGrant {
+    pub grantee: AgentPubKey,
+    pub role: Role,
+}
+
+// committed by the grantee, so that validation can find the grant on the grantee's own source chain
+#[hdk_entry_helper]
+#[derive(Clone)]
+pub struct RoleClaim {
+    pub grant_ah: ActionHash,
+}
+
+impl RoleClaim {
+    // the grant this claim points to, if it was made by the community activator for the claimant
+    pub fn grant_for(&self, claimant: &AgentPubKey) -> ExternResult<Result<RoleGrant, String>> {
+        let record = must_get_valid_record(self.grant_ah.clone())?;
+        let grantor = record.action().author().clone();
+        if !is_community_activator_at(&grantor, record.action().prev_action().cloned())? {
+            return Ok(Err(String::from("role grants can only be made by the community activator")));
+        }
+        let grant = match record.entry().to_app_option::<RoleGrant>() {
+            Ok(Some(grant)) => grant,
+            _ => return Ok(Err(String::from("the claimed action does not create a role grant"))),
+        };
+        if grant.grantee != *claimant {
+            return Ok(Err(String::from("a role grant can only be claimed by its grantee")));
+        }
+        Ok(Ok(grant))
+    }
+}
+
+// authored by the community activator, ends the role the grant gave
+#[hdk_entry_helper]
+#[derive(Clone)]
+pub struct RoleRevocation {
+    pub grant_ah: ActionHash,
+}
+
+impl RoleRevocation {
+    // the grant this revokes, if the action creates one
+    pub fn grant(&self) -> ExternResult<Result<RoleGrant, String>> {
+        match must_get_valid_record(self.grant_ah.clone())?.entry().to_app_option::<RoleGrant>() {
+            Ok(Some(grant)) => Ok(Ok(grant)),
+            _ => Ok(Err(String::from("the revoked action does not create a role grant"))),
+        }
+    }
+}
+
+// committed by an agent holding roles, pins a position on the community activator's chain. Validation can't
+// prove that a revocation is missing from the DHT, so it reads the revocations up to the agent's latest checkpoint
+#[hdk_entry_helper]
+#[derive(Clone)]
+pub struct RoleCheckpoint {
+    pub community_activator_chain_top: ActionHash,
+}
+
+impl RoleCheckpoint {
+    // the pinned action, if its author was the community activator when they committed it
+    pub fn pinned_action(&self) -> ExternResult<Result<Action, String>> {
+        let action = must_get_action(self.community_activator_chain_top.clone())?.action().clone();
+        if !is_community_activator_at(action.author(), Some(self.community_activator_chain_top.clone()))? {
+            return Ok(Err(String::from(
+                "a role checkpoint can only pin an action of the community activator",
+            )));
+        }
+        Ok(Ok(action))
+    }
+}
+
+// the community activator holds every role. Other agents need to have claimed a grant for it on their source chain
+// up to `chain_top`, and a checkpoint that pins an action at most `ROLE_CHECKPOINT_LEASE_MICROS` before `at`.
+// A grant revoked up to the checkpoint gives no role, and an agent who stops checkpointing loses their roles.
+pub fn has_role(
+    agent: &AgentPubKey,
+    role: &Role,
+    chain_top: Option<ActionHash>,
+    at: Timestamp,
+) -> ExternResult<bool> {
+    has_unrevoked_role(agent, role, chain_top, at, &BTreeSet::new())
+}
+
+// as `has_role`, also ignoring the claims of `revoked_grant_ahs`, for the coordinator to count the revocations
+// committed since the agent's latest checkpoint
+pub fn has_unrevoked_role(
+    agent: &AgentPubKey,
+    role: &Role,
+    chain_top: Option<ActionHash>,
+    at: Timestamp,
+    revoked_grant_ahs: &BTreeSet<ActionHash>,
+) -> ExternResult<bool> {
+    let entries = role_entries(agent, chain_top)?;
+    if community_activator_handover_in(agent, &entries)?.is_some() {
+        return Ok(true);
+    }
+    let checkpoint = match entries
+        .iter()
+        .rev()
+        .find_map(|entry| RoleCheckpoint::try_from(entry.clone()).ok())
+    {
+        Some(checkpoint) => checkpoint,
+        None => return Ok(false),
+    };
+    let pinned_action = match checkpoint.pinned_action()? {
+        Ok(pinned_action) => pinned_action,
+        Err(_) => return Ok(false),
+    };
+    if at.as_micros() > pinned_action.timestamp().as_micros() + ROLE_CHECKPOINT_LEASE_MICROS {
+        return Ok(false);
+    }
+    let mut revoked_grant_ahs = revoked_grant_ahs.clone();
+    revoked_grant_ahs.extend(revoked_grant_ahs_at(
+        pinned_action.author().clone(),
+        checkpoint.community_activator_chain_top,
+    )?);
+    for entry in entries {
+        if let Ok(claim) = RoleClaim::try_from(entry) {
+            if revoked_grant_ahs.contains(&claim.grant_ah) {
+                continue;
+            }
+            if let Ok(grant) = claim.grant_for(agent)? {
+                if grant.role.includes(role) {
+                    return Ok(true);
+                }
+            }
+        }
+    }
+    Ok(false)
+}
+
+// the grants revoked on the community activator's chain up to `chain_top`, and on the chains of the community
+// activators before them up to their handovers. A handover made by a steward quorum ends the walk, the revocations
+// of a community activator whose key was lost have to be made again by their successor.
+fn revoked_grant_ahs_at(
+    community_activator: AgentPubKey,
+    chain_top: ActionHash,
+) -> ExternResult<BTreeSet<ActionHash>> {
+    let mut revoked_grant_ahs = BTreeSet::new();
+    let mut next = Some((community_activator, chain_top));
+    while let Some((community_activator, chain_top)) = next.take() {
+        let mut claimed_handover_ah = None;
+        for entry in entries_of(&community_activator, Some(chain_top), &REVOCATION_ENTRY_INDEXES)? {
+            if let Ok(revocation) = RoleRevocation::try_from(entry.clone()) {
+                revoked_grant_ahs.insert(revocation.grant_ah);
+            } else if let Ok(claim) = CommunityActivatorClaim::try_from(entry) {
+                claimed_handover_ah = Some(claim.handover_ah);
+            }
+        }
+        if let Some(handover_ah) = claimed_handover_ah {
+            let record = must_get_valid_record(handover_ah.clone())?;
+            if let Ok(Some(handover)) = record.entry().to_app_option::<CommunityActivatorHandover>() {
+                let predecessor = handover.predecessor()?;
+                if *record.action().author() == predecessor {
+                    next = Some((predecessor, handover_ah));
+                }
+            }
+        }
+    }
+    Ok(revoked_grant_ahs)
+}
+
+// whether `agent` is the community activator at the end of the chain of handovers as seen from their
+// source chain up to `chain_top`
+pub fn is_community_activator_at(agent: &AgentPubKey, chain_top: Option<ActionHash>) -> ExternResult<bool> {
+    Ok(community_activator_handover_in(agent, &role_entries(agent, chain_top)?)?.is_some())
+}
+
+// entry indexes of `RoleClaim`, `CommunityActivatorHandover`, `CommunityActivatorClaim` and `RoleCheckpoint` in
+// the sensemaker integrity zome's `EntryTypes`, the only entries roles are read from
+const ROLE_ENTRY_INDEXES: [u8; 4] = [12, 13, 14, 16];
+
+// entry indexes of `CommunityActivatorClaim` and `RoleRevocation`, the entries revocations are read from
+const REVOCATION_ENTRY_INDEXES: [u8; 2] = [14, 15];
+
+// the role claims, handovers, community activator claims and role checkpoints on the agent's chain.
+// Oldest first, so that a handover ends the rights a claim earlier on the chain gave.
+pub(crate) fn role_entries(agent: &AgentPubKey, chain_top: Option<ActionHash>) -> ExternResult<Vec<Entry>> {
+    entries_of(agent, chain_top, &ROLE_ENTRY_INDEXES)
+}
+
+// the public app entries at `entry_indexes` on the agent's chain, oldest first. Only the entries of those types
+// are fetched, any other public app entry at the same index won't deserialize as one.
+fn entries_of(
+    agent: &AgentPubKey,
+    chain_top: Option<ActionHash>,
+    entry_indexes: &[u8],
+) -> ExternResult<Vec<Entry>> {
+    let chain_top = match chain_top {
+        Some(chain_top) => chain_top,
+        None => return Ok(vec![]),
+    };
+    let mut activities = must_get_agent_activity(agent.clone(), ChainFilter::new(chain_top))?;
+    activities.sort_by_key(|activity| activity.action.hashed.content.action_seq());
+    let mut entries = vec![];
+    for activity in activities {
+        if let Action::Create(create) = activity.action.hashed.content {
+            if let EntryType::App(app_entry_def) = &create.entry_type {
+                if app_entry_def.visibility.is_public()
+                    && entry_indexes.contains(&app_entry_def.entry_index.0)
+                {
+                    entries.push(must_get_entry(create.entry_hash)?.into_content());
+                }
+            }
+        }
+    }
+    Ok(entries)
+}
@@ -0,0 +1,213 @@
+use std::collections::BTreeSet;
+
+use hdi::prelude::*;
+
+use crate::{role::role_entries, Properties, Role, RoleClaim};
+
+// designates the next community activator. Authored by the current community activator, or by anyone
+// carrying approvals from a quorum of stewards when the current community activator's key is lost.
+// A quorum can't hand over from a handover the community activator has already handed over from, as far as
+// the position it pins on their chain shows. When several handovers still follow the same one, the one
+// authored by the community activator it hands over from wins, then the one with the lowest action hash.
+#[hdk_entry_helper]
+#[derive(Clone)]
+pub struct CommunityActivatorHandover {
+    pub successor: AgentPubKey,
+    // the handover this one follows, `None` when handing over from the community activator in the DNA properties
+    pub previous_handover_ah: Option<ActionHash>,
+    pub steward_approvals: Vec<StewardApproval>,
+    // the latest action seen on the chain of the community activator handed over from, only set by a quorum
+    pub predecessor_chain_top: Option<ActionHash>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StewardApproval {
+    pub steward: AgentPubKey,
+    // the steward's claim of their `Steward` role grant
+    pub role_claim_ah: ActionHash,
+    // the handover the approval hands over from, as signed
+    pub previous_handover_ah: Option<ActionHash>,
+    // signature over the `HandoverApproval` of the handover
+    pub signature: Signature,
+}
+
+// what a steward signs to approve a handover, tied to the previous handover so it can't be replayed
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HandoverApproval {
+    pub successor: AgentPubKey,
+    pub previous_handover_ah: Option<ActionHash>,
+}
+
+// committed by the successor, so that validation can find the handover on the successor's own source chain
+#[hdk_entry_helper]
+#[derive(Clone)]
+pub struct CommunityActivatorClaim {
+    pub handover_ah: ActionHash,
+}
+
+impl CommunityActivatorHandover {
+    pub fn approval(&self) -> HandoverApproval {
+        HandoverApproval {
+            successor: self.successor.clone(),
+            previous_handover_ah: self.previous_handover_ah.clone(),
+        }
+    }
+
+    // the community activator this handover is from
+    pub fn predecessor(&self) -> ExternResult<AgentPubKey> {
+        match self.previous_handover_ah.clone() {
+            Some(previous_handover_ah) => Ok(get_handover(previous_handover_ah)?.successor),
+            None => Ok(Properties::get()?.sensemaker_config.community_activator.into()),
+        }
+    }
+
+    // `chain_top` is the action before the handover on the author's chain
+    pub fn check(&self, author: &AgentPubKey, chain_top: Option<ActionHash>) -> ExternResult<Result<(), String>> {
+        let entries = role_entries(author, chain_top)?;
+        let follows_same_handover = entries.iter().any(|entry| {
+            matches!(
+                CommunityActivatorHandover::try_from(entry.clone()),
+                Ok(handover) if handover.previous_handover_ah == self.previous_handover_ah
+            )
+        });
+        if follows_same_handover {
+            return Ok(Err(String::from(
+                "this agent has already committed a handover following the same handover",
+            )));
+        }
+        if *author == self.predecessor()?
+            && community_activator_handover_in(author, &entries)? == Some(self.previous_handover_ah.clone())
+        {
+            return Ok(Ok(()));
+        }
+        let quorum = match Properties::get()?.sensemaker_config.steward_quorum {
+            Some(quorum) if quorum > 0 => quorum as usize,
+            _ => {
+                return Ok(Err(String::from(
+                    "only the current community activator can hand over, no steward quorum is configured",
+                )))
+            }
+        };
+        if let Err(error) = self.check_predecessor_has_not_handed_over()? {
+            return Ok(Err(error));
+        }
+        let approval = self.approval();
+        let mut stewards: BTreeSet<AgentPubKey> = BTreeSet::new();
+        for steward_approval in self.steward_approvals.iter() {
+            if !verify_signature(
+                steward_approval.steward.clone(),
+                steward_approval.signature.clone(),
+                &approval,
+            )? {
+                return Ok(Err(format!(
+                    "approval from {} has an invalid signature",
+                    steward_approval.steward
+                )));
+            }
+            let record = must_get_valid_record(steward_approval.role_claim_ah.clone())?;
+            let is_steward = match record.entry().to_app_option::<RoleClaim>() {
+                Ok(Some(claim)) if *record.action().author() == steward_approval.steward => {
+                    match claim.grant_for(&steward_approval.steward)? {
+                        Ok(grant) => grant.role == Role::Steward,
+                        Err(_) => false,
+                    }
+                }
+                _ => false,
+            };
+            if !is_steward {
+                return Ok(Err(format!(
+                    "approval from {} is not backed by a steward role claim",
+                    steward_approval.steward
+                )));
+            }
+            stewards.insert(steward_approval.steward.clone());
+        }
+        if stewards.len() < quorum {
+            return Ok(Err(format!(
+                "a handover needs approvals from {} stewards, it has {}",
+                quorum,
+                stewards.len()
+            )));
+        }
+        Ok(Ok(()))
+    }
+
+    // a quorum handover pins the predecessor's chain, which mustn't already hand over from the same handover
+    fn check_predecessor_has_not_handed_over(&self) -> ExternResult<Result<(), String>> {
+        let predecessor_chain_top = match self.predecessor_chain_top.clone() {
+            Some(predecessor_chain_top) => predecessor_chain_top,
+            None => {
+                return Ok(Err(String::from(
+                    "a handover by a steward quorum has to pin the chain of the community activator it hands over from",
+                )))
+            }
+        };
+        let predecessor = self.predecessor()?;
+        if *must_get_action(predecessor_chain_top.clone())?.action().author() != predecessor {
+            return Ok(Err(String::from(
+                "the pinned action is not on the chain of the community activator handed over from",
+            )));
+        }
+        let predecessor_handed_over = role_entries(&predecessor, Some(predecessor_chain_top))?
+            .into_iter()
+            .any(|entry| {
+                matches!(
+                    CommunityActivatorHandover::try_from(entry),
+                    Ok(handover) if handover.previous_handover_ah == self.previous_handover_ah
+                )
+            });
+        if predecessor_handed_over {
+            return Ok(Err(String::from(
+                "the community activator has already handed over from this handover",
+            )));
+        }
+        Ok(Ok(()))
+    }
+}
+
+impl CommunityActivatorClaim {
+    pub fn check(&self, author: &AgentPubKey) -> ExternResult<Result<(), String>> {
+        if get_handover(self.handover_ah.clone())?.successor != *author {
+            return Ok(Err(String::from(
+                "a community activator handover can only be claimed by its successor",
+            )));
+        }
+        Ok(Ok(()))
+    }
+}
+
+// the handover `agent` is the community activator through, given their role entries oldest first:
+// `Some(None)` for the community activator in the DNA properties, `Some(Some(handover_ah))` for a successor
+// from their claim of `handover_ah` on. Committing the next handover ends it, so only the agent at the end of
+// the chain of handovers holds it.
+pub(crate) fn community_activator_handover_in(
+    agent: &AgentPubKey,
+    entries: &[Entry],
+) -> ExternResult<Option<Option<ActionHash>>> {
+    let mut community_activator_handover = match Properties::is_community_activator(agent.clone())? {
+        true => Some(None),
+        false => None,
+    };
+    for entry in entries {
+        if let Ok(claim) = CommunityActivatorClaim::try_from(entry.clone()) {
+            if claim.check(agent)?.is_ok() {
+                community_activator_handover = Some(Some(claim.handover_ah));
+            }
+        } else if let Ok(handover) = CommunityActivatorHandover::try_from(entry.clone()) {
+            if community_activator_handover == Some(handover.previous_handover_ah) {
+                community_activator_handover = None;
+            }
+        }
+    }
+    Ok(community_activator_handover)
+}
+
+fn get_handover(handover_ah: ActionHash) -> ExternResult<CommunityActivatorHandover> {
+    let record = must_get_valid_record(handover_ah)?;
+    match record.entry().to_app_option::<CommunityActivatorHandover>() {
+        Ok(Some(handover)) => Ok(handover),
+        _ => Err(wasm_error!(WasmErrorInner::Guest(String::from(
+            "the action does not create a community activator handover"
+        )))),
+    }
+}
@@ -36,7 +36,20 @@ export interface AppletConfig {
   },
   cultural_contexts: {
     [contextName: string]: EntryHash,
-  }
+  },
+  version?: number,
+  previous_config_eh?: EntryHash,
+}
+
+export interface GetAppletConfigAtVersionInput {
+  name: string,
+  version: number,
+}
+
+export interface UnregisterAppletInput {
+  applet_name: string,
+  // keep the assessments along the applet's dimensions for archival
+  keep_assessments: boolean,
 }
 
 /**
@@ -54,6 +67,25 @@ export interface AppletConfigInput {
   dimensions?: Array<ConfigDimension>;
 }
 
+/**
+ * A problem found in an applet config by `check_applet_config`
+ */
+export type ConfigProblem =
+  | { DuplicateName: { kind: string, name: string } }
+  | { InvalidRange: { range: string, error: string } }
+  | { UnknownRange: { dimension: string } }
+  | { SubjectiveValidatedDimension: { dimension: string } }
+  | { InvalidProgram: { method: string, error: string } }
+  | { ObjectiveInputDimension: { method: string, dimension: string } }
+  | { SubjectiveOutputDimension: { method: string, dimension: string } }
+  | { MethodValidationMismatch: { method: string, dimension: string } }
+  | { UnknownMethodDimension: { method: string, dimension: string } }
+  | { UnknownResourceDef: { context: string, resource_def: string } }
+  | { DanglingThresholdDimension: { context: string, dimension: string } }
+  | { SubjectiveThresholdDimension: { context: string, dimension: string } }
+  | { UnknownOrderByDimension: { context: string, dimension: string } }
+  | { SubjectiveOrderByDimension: { context: string, dimension: string } }
+
 /**
  * The resource renderer interface.
  *
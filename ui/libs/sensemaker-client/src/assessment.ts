@@ -21,6 +21,23 @@ export type Assessment = CreateAssessmentInput & {
 export interface GetAssessmentsForResourceInput {
     resource_ehs?: ResourceEh[],
     dimension_ehs?: DimensionEh[],
+    resource_def_ehs?: ResourceDefEh[],
+}
+
+export interface GetMyAssessmentsInput {
+    dimension_ehs?: DimensionEh[],
+    resource_def_ehs?: ResourceDefEh[],
+}
+
+export type GetAssessmentsByAgentInput = GetMyAssessmentsInput & {
+    agent: AgentPubKey,
+}
+
+export interface GetAssessmentsInTimeRangeInput {
+    resource_def_eh: ResourceDefEh,
+    from: Timestamp, // inclusive
+    to: Timestamp, // exclusive
+    dimension_ehs?: DimensionEh[],
 }
 
 export type AssessmentEh = EntryHash
\ No newline at end of file
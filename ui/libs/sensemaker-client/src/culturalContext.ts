@@ -9,19 +9,26 @@ interface CoreCulturalContext {
 export type CulturalContext = CoreCulturalContext & {
     resource_def_eh: EntryHash,
     order_by: Array<[EntryHash, OrderingKind]>,
-    thresholds: Array<Threshold>,
+    thresholds: Array<Threshold>, // all must be met, along with the threshold_condition if there is one
+    threshold_condition?: ThresholdCondition<Threshold>,
 }
 
 export type ConfigCulturalContext = CoreCulturalContext & {
     resource_def: ConfigResourceDef,
     order_by: Array<[ConfigDimension, OrderingKind]>,
     thresholds: Array<ConfigThreshold>,
+    threshold_condition?: ThresholdCondition<ConfigThreshold>,
 }
 
 export interface ContextResult {
     context_eh: EntryHash,
     dimension_ehs: Array<EntryHash>, // of objective dimensions
-    result: Array<[EntryHash, Array<RangeValue>]>,
+    result: Array<[EntryHash, Array<RangeValue | null>]>, // null where a resource has no assessment on that dimension
+}
+
+export interface GetCulturalContextByNameInput {
+    applet_eh: EntryHash,
+    name: string,
 }
 
 export interface ComputeContextInput {
@@ -52,7 +59,13 @@ export interface OrderingKindSmallest {
     Smallest: null,
 }
 
-export type ThresholdKind = ThresholdKindGreaterThan | ThresholdKindLessThan | ThresholdKindEqual
+export type ThresholdCondition<T> =
+    | { Threshold: T }
+    | { And: Array<ThresholdCondition<T>> }
+    | { Or: Array<ThresholdCondition<T>> }
+    | { Not: ThresholdCondition<T> }
+
+export type ThresholdKind = ThresholdKindGreaterThan | ThresholdKindLessThan | ThresholdKindEqual | ThresholdKindNotEqual | ThresholdKindGreaterOrEqual | ThresholdKindLessOrEqual | ThresholdKindBetween | ThresholdKindInSet
 
 export interface ThresholdKindGreaterThan {
     GreaterThan: null,
@@ -65,3 +78,23 @@ export interface ThresholdKindLessThan {
 export interface ThresholdKindEqual {
     Equal: null,
 }
+
+export interface ThresholdKindNotEqual {
+    NotEqual: null,
+}
+
+export interface ThresholdKindGreaterOrEqual {
+    GreaterOrEqual: null,
+}
+
+export interface ThresholdKindLessOrEqual {
+    LessOrEqual: null,
+}
+
+export interface ThresholdKindBetween {
+    Between: RangeValue, // inclusive upper bound, the threshold's value is the inclusive lower bound
+}
+
+export interface ThresholdKindInSet {
+    InSet: Array<RangeValue>, // met by the threshold's value or any of these
+}
@@ -3,6 +3,8 @@ import { Range } from "./range"
 interface CoreDimension {
     name: string,
     computed: boolean,
+    // only for computed dimensions, whose assessments then need a dataset from a method that requires validation
+    requires_validation?: boolean,
 }
 
 export type Dimension = CoreDimension & {
@@ -1,6 +1,7 @@
 export * from "./dimension"
 export * from "./range"
 export * from "./resourceDef"
+export * from "./role"
 export * from "./assessment"
 export * from "./method"
 export * from "./culturalContext"
@@ -1,6 +1,7 @@
 import { EntryHash } from "@holochain/client"
 import { ConfigDimension, Dimension } from "./dimension"
 import { ConfigResourceDef } from "./resourceDef"
+import { RangeValue } from "./range"
 
 interface CoreMethod {
     name: string,
@@ -25,12 +26,10 @@ export interface RunMethodInput {
 }
 export interface DataSet {
     from: EntryHash,
-    data_points: {
-        [key: string]: Array<EntryHash>, // key cannot be of type Uint8Array, specifying as string for now as we are not currently using `DataSet`
-    }
+    data_points: Array<[EntryHash, Array<EntryHash>]>, // [dimension_eh, assessment_ehs] in the order of the method's input dimensions
 }
 
-export type Program = ProgramSum | ProgramAverage
+export type Program = ProgramSum | ProgramAverage | ProgramCount | ProgramMode | ProgramExpression
 
 export interface ProgramSum {
     Sum: null,
@@ -40,6 +39,46 @@ export interface ProgramAverage {
     Average: null,
 }
 
+export interface ProgramCount {
+    Count: RangeValue,
+}
+
+export interface ProgramMode {
+    Mode: null,
+}
+
+export interface ProgramExpression {
+    Expression: Expression,
+}
+
+export type Expression =
+    | { Constant: RangeValue }
+    | { Dimension: number } // index into the method's input dimensions
+    | { Count: Expression }
+    | { Sum: Expression }
+    | { Average: Expression }
+    | { Min: Expression }
+    | { Max: Expression }
+    | { Median: Expression }
+    | { Add: [Expression, Expression] }
+    | { Subtract: [Expression, Expression] }
+    | { Multiply: [Expression, Expression] }
+    | { Divide: [Expression, Expression] }
+    | { Weighted: Array<[number, Expression]> }
+    | { If: { condition: Condition, then: Expression, otherwise: Expression } }
+
+export type Condition =
+    | { Compare: [Expression, Comparison, Expression] }
+    | { And: Array<Condition> }
+    | { Or: Array<Condition> }
+    | { Not: Condition }
+
+export type Comparison =
+    | { GreaterThan: null }
+    | { LessThan: null }
+    | { Equal: null }
+    | { NotEqual: null }
+
 export interface GetMethodsForDimensionQueryParams {
     dimensionType: "input" | "output",
     dimensionEh: EntryHash,
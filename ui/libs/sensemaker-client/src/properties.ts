@@ -5,6 +5,8 @@ export interface SensemakerConfig {
     neighbourhood: string,
     community_activator: AgentPubKeyB64,
     wizard_version: string,
+    // number of stewards that can together hand over the community activator role
+    steward_quorum?: number,
 }
 
 export interface Properties {
@@ -3,7 +3,7 @@ export interface Range {
     kind: RangeKind,
 }
 
-export type RangeKind = RangeKindInteger | RangeKindFloat
+export type RangeKind = RangeKindInteger | RangeKindFloat | RangeKindTag | RangeKindEmoji | RangeKindTagTree
 
 
 export interface RangeKindInteger {
@@ -20,7 +20,21 @@ export interface RangeKindFloat {
     }
 }
 
-export type RangeValue = RangeValueInteger | RangeValueFloat
+export interface RangeKindTag {
+    Tag: Array<string>,
+}
+
+export interface RangeKindEmoji {
+    Emoji: Array<string>, // each a single unicode scalar value
+}
+
+export interface RangeKindTagTree {
+    TagTree: {
+        [branch: string]: Array<string>,
+    }
+}
+
+export type RangeValue = RangeValueInteger | RangeValueFloat | RangeValueTag | RangeValueEmoji | RangeValueTagTree
 
 export interface RangeValueInteger {
     Integer: number,
@@ -29,3 +43,15 @@ export interface RangeValueInteger {
 export interface RangeValueFloat {
     Float: number,
 }
+
+export interface RangeValueTag {
+    Tag: string,
+}
+
+export interface RangeValueEmoji {
+    Emoji: string,
+}
+
+export interface RangeValueTagTree {
+    TagTree: [string, string], // [branch, leaf]
+}
@@ -0,0 +1,54 @@
+import { ActionHash, AgentPubKey } from "@holochain/client";
+
+// a steward can do everything the other roles can
+export type Role =
+  | { Steward: null }
+  | { MethodAuthor: null }
+  | { TrayCurator: null }
+
+export interface RoleGrant {
+  grantee: AgentPubKey,
+  role: Role,
+}
+
+export interface RoleClaim {
+  grant_ah: ActionHash,
+}
+
+// authored by the community activator, ends the role the grant gave
+export interface RoleRevocation {
+  grant_ah: ActionHash,
+}
+
+// pins a position on the community activator's chain, roles are checked against the revocations up to there
+export interface RoleCheckpoint {
+  community_activator_chain_top: ActionHash,
+}
+
+export interface GrantRoleInput {
+  grantee: AgentPubKey,
+  role: Role,
+}
+
+export interface StewardApproval {
+  steward: AgentPubKey,
+  // the steward's claim of their Steward role grant
+  role_claim_ah: ActionHash,
+  // the handover the approval hands over from, as signed
+  previous_handover_ah: ActionHash | null,
+  signature: Uint8Array,
+}
+
+export interface CommunityActivatorHandover {
+  successor: AgentPubKey,
+  previous_handover_ah: ActionHash | null,
+  steward_approvals: Array<StewardApproval>,
+  // the latest action seen on the chain of the community activator handed over from, only set by a quorum
+  predecessor_chain_top: ActionHash | null,
+}
+
+export interface HandOverCommunityActivatorInput {
+  successor: AgentPubKey,
+  // only needed when the current community activator isn't the one handing over
+  steward_approvals: Array<StewardApproval>,
+}
@@ -1,5 +1,5 @@
 import { ActionHash, AgentPubKey, AppAgentCallZomeRequest, AppAgentClient, EntryHash, EntryHashB64, Record as HolochainRecord, RoleName } from '@holochain/client';
-import { AppletConfig, AppletConfigInput, Assessment, AssessmentControlConfig, AssessmentControlRegistrationInput, AssessmentTrayConfig, ComputeContextInput, CreateAssessmentInput, CulturalContext, Dimension, GetAssessmentsForResourceInput, GetMethodsForDimensionQueryParams, Method, Range, ResourceDef, RunMethodInput } from './index';
+import { AppletConfig, AppletConfigInput, Assessment, AssessmentControlConfig, AssessmentControlRegistrationInput, AssessmentTrayConfig, ComputeContextInput, ConfigProblem, CreateAssessmentInput, CulturalContext, Dimension, GetAppletConfigAtVersionInput, GetAssessmentsByAgentInput, GetAssessmentsForResourceInput, GetAssessmentsInTimeRangeInput, GetCulturalContextByNameInput, GetMyAssessmentsInput, GetMethodsForDimensionQueryParams, GrantRoleInput, HandOverCommunityActivatorInput, Method, Range, ResourceDef, Role, RunMethodInput, StewardApproval, UnregisterAppletInput } from './index';
 import { Option } from './utils';
 
 export class SensemakerService {
@@ -68,6 +68,18 @@ export class SensemakerService {
   async getAssessmentsForResources(getAssessmentsInput: GetAssessmentsForResourceInput): Promise<Record<EntryHashB64, Array<Assessment>>> {
     return this.callZome('get_assessments_for_resources', getAssessmentsInput);
   }
+
+  async getMyAssessments(getMyAssessmentsInput: GetMyAssessmentsInput): Promise<Array<Assessment>> {
+    return this.callZome('get_my_assessments', getMyAssessmentsInput);
+  }
+
+  async getAssessmentsByAgent(getAssessmentsByAgentInput: GetAssessmentsByAgentInput): Promise<Array<Assessment>> {
+    return this.callZome('get_assessments_by_agent', getAssessmentsByAgentInput);
+  }
+
+  async getAssessmentsInTimeRange(getAssessmentsInTimeRangeInput: GetAssessmentsInTimeRangeInput): Promise<Array<Assessment>> {
+    return this.callZome('get_assessments_in_time_range', getAssessmentsInTimeRangeInput);
+  }
   
   async createMethod(method: Method): Promise<HolochainRecord> {
     return this.callZome('create_method', method);
@@ -121,10 +133,26 @@ export class SensemakerService {
     return this.callZome('get_cultural_context', culturalContextEh);
   }
 
+  async getCulturalContexts(): Promise<Array<HolochainRecord>> {
+    return this.callZome('get_cultural_contexts', null);
+  }
+
+  async getCulturalContextsForResourceDef(resourceDefEh: EntryHash): Promise<Array<HolochainRecord>> {
+    return this.callZome('get_cultural_contexts_for_resource_def', resourceDefEh);
+  }
+
+  async getCulturalContextByName(input: GetCulturalContextByNameInput): Promise<HolochainRecord | null> {
+    return this.callZome('get_cultural_context_by_name', input);
+  }
+
   async computeContext(computeContextInput: ComputeContextInput): Promise<Array<EntryHash>> {
     return this.callZome('compute_context', computeContextInput);
   }
 
+  async getLatestContextResult(culturalContextEh: EntryHash): Promise<HolochainRecord | null> {
+    return this.callZome('get_latest_context_result', culturalContextEh);
+  }
+
   async checkIfAppletConfigExists(appletName: string): Promise<Option<AppletConfig>> {
     return this.callZome('check_if_applet_config_exists', appletName);
   }
@@ -132,6 +160,70 @@ export class SensemakerService {
   async registerApplet(appletConfig: AppletConfigInput): Promise<AppletConfig> {
     return this.callZome('register_applet', appletConfig);
   }
+
+  async checkAppletConfig(appletConfig: AppletConfigInput): Promise<Array<ConfigProblem>> {
+    return this.callZome('check_applet_config', appletConfig);
+  }
+
+  async updateAppletConfig(appletConfig: AppletConfigInput): Promise<AppletConfig> {
+    return this.callZome('update_applet_config', appletConfig);
+  }
+
+  async getAppletConfigAtVersion(input: GetAppletConfigAtVersionInput): Promise<Option<AppletConfig>> {
+    return this.callZome('get_applet_config_at_version', input);
+  }
+
+  async getAppletConfigs(): Promise<Array<HolochainRecord>> {
+    return this.callZome('get_applet_configs', null);
+  }
+
+  async getAppletConfigForResourceDef(resourceDefEh: EntryHash): Promise<Option<HolochainRecord>> {
+    return this.callZome('get_applet_config_for_resource_def', resourceDefEh);
+  }
+
+  async unregisterApplet(input: UnregisterAppletInput): Promise<void> {
+    return this.callZome('unregister_applet', input);
+  }
+
+  async grantRole(input: GrantRoleInput): Promise<HolochainRecord> {
+    return this.callZome('grant_role', input);
+  }
+
+  async claimRole(grantAh: ActionHash): Promise<HolochainRecord> {
+    return this.callZome('claim_role', grantAh);
+  }
+
+  async revokeRole(grantAh: ActionHash): Promise<HolochainRecord> {
+    return this.callZome('revoke_role', grantAh);
+  }
+
+  async refreshRoleCheckpoint(): Promise<void> {
+    return this.callZome('refresh_role_checkpoint', null);
+  }
+
+  async getRoleGrantsForAgent(agent: AgentPubKey): Promise<Array<HolochainRecord>> {
+    return this.callZome('get_role_grants_for_agent', agent);
+  }
+
+  async iHaveRole(role: Role): Promise<boolean> {
+    return this.callZome('i_have_role', role);
+  }
+
+  async handOverCommunityActivator(input: HandOverCommunityActivatorInput): Promise<HolochainRecord> {
+    return this.callZome('hand_over_community_activator', input);
+  }
+
+  async approveCommunityActivatorHandover(successor: AgentPubKey): Promise<StewardApproval> {
+    return this.callZome('approve_community_activator_handover', successor);
+  }
+
+  async claimCommunityActivator(handoverAh: ActionHash): Promise<HolochainRecord> {
+    return this.callZome('claim_community_activator', handoverAh);
+  }
+
+  async getCurrentCommunityActivator(): Promise<AgentPubKey> {
+    return this.callZome('get_current_community_activator', null);
+  }
   
   async registerAssessmentControl(assessmentControlRegistration: AssessmentControlRegistrationInput) : Promise<HolochainRecord> {
     return this.callZome('register_assessment_control', assessmentControlRegistration, 'assessment_tray');
@@ -141,6 +233,17 @@ export class SensemakerService {
     return this.callZome('get_assessment_control_registrations', null, 'assessment_tray');
   }
 
+  /**
+   * Move the links this agent made from the unsharded anchors of both zomes to their shards.
+   * Links made by other agents are left for them to move, and stay on the unsharded anchors,
+   * which reads keep scanning, until they do
+   * @returns the number of links made from the shards, not counting targets already linked there
+   */
+  async migrateShardedAnchors(): Promise<number> {
+    return await this.callZome('migrate_sharded_anchors', null)
+      + await this.callZome('migrate_sharded_anchors', null, 'assessment_tray');
+  }
+
   private callZome(fn_name: string, payload: any, zomeName = this.zomeName) {
     const req: AppAgentCallZomeRequest = {
       role_name: this.roleName,
@@ -15,6 +15,7 @@ import {
   AppletConfigInput,
   Assessment,
   ComputeContextInput,
+  ContextResult,
   CreateAssessmentInput,
   CulturalContext,
   Dimension,
@@ -24,6 +25,7 @@ import {
   ResourceDef,
   RunMethodInput,
   SignalPayload,
+  UnregisterAppletInput,
   AssessmentControlConfig,
   AssessmentControlRegistrationInput,
   GetMethodsForDimensionQueryParams,
@@ -397,6 +399,12 @@ export class SensemakerStore {
     return contextResult;
   }
 
+  async getLatestContextResult(contextEh: EntryHash): Promise<EntryRecord<ContextResult> | null> {
+    const record = await this.service.getLatestContextResult(contextEh);
+    if(!record) return null;
+    return new EntryRecord<ContextResult>(record)
+  }
+
   async getAssessmentTrayConfig(assessmentTrayEh: EntryHash): Promise<EntryRecord<AssessmentTrayConfig> | null> {
     const record = await this.service.getAssessmentTrayConfig(assessmentTrayEh);
     if(!record) return null;
@@ -496,6 +504,19 @@ export class SensemakerStore {
     return await this.updateAppletConfig(appletConfig);
   }
 
+  async updateApplet(appletConfigInput: AppletConfigInput): Promise<AppletConfig> {
+    const appletConfig = await this.service.updateAppletConfig(appletConfigInput);
+    return await this.updateAppletConfig(appletConfig);
+  }
+
+  async unregisterApplet(input: UnregisterAppletInput): Promise<void> {
+    await this.service.unregisterApplet(input);
+    this.contexts.update((contexts) => {
+      contexts.delete(input.applet_name);
+      return contexts;
+    });
+  }
+
   updateActiveMethod(resourceDefEh: EntryHashB64, methodEh: EntryHashB64) {
     this._activeMethod.update((activeMethods) => {
       activeMethods[resourceDefEh] = methodEh;
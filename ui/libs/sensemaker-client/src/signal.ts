@@ -1,7 +1,13 @@
+import { EntryHash } from "@holochain/client";
 import { Assessment } from "./assessment";
 
 export type SignalPayload = 
 | {
     type: "NewAssessment",
     assessment: Assessment,
-}
\ No newline at end of file
+}
+| {
+    type: "LiveMethodResult",
+    method_eh: EntryHash,
+    assessment: Assessment,
+}
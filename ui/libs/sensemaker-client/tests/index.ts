@@ -7,6 +7,18 @@ import range from './sensemaker_dna/sensemaker/range';
 import dashboard from './sensemaker_dna/sensemaker/dashboard';
 import method from './sensemaker_dna/sensemaker/method';
 import resource_def from './sensemaker_dna/sensemaker/resource_def';
+import categorical_range from './sensemaker_dna/sensemaker/categorical_range';
+import program from './sensemaker_dna/sensemaker/program';
+import live_method from './sensemaker_dna/sensemaker/live_method';
+import context_ordering from './sensemaker_dna/sensemaker/context_ordering';
+import roles from './sensemaker_dna/sensemaker/roles';
+import succession from './sensemaker_dna/sensemaker/succession';
+import structural_validation from './sensemaker_dna/sensemaker/structural_validation';
+import assessment_author from './sensemaker_dna/sensemaker/assessment_author';
+import assessment_time_range from './sensemaker_dna/sensemaker/assessment_time_range';
+import resource_def_assessments from './sensemaker_dna/sensemaker/resource_def_assessments';
+import sharding from './sensemaker_dna/sensemaker/sharding';
+import batched_reads from './sensemaker_dna/sensemaker/batched_reads';
 import assessment_tray_config from './sensemaker_dna/assessment_control/assessment_tray_config';
 import assessment_control_registration from './sensemaker_dna/assessment_control/assessment_control_registration';
 
@@ -19,5 +31,17 @@ sensemaker_community_activator();
 dashboard();
 method();
 resource_def();
+categorical_range();
+program();
+live_method();
+context_ordering();
+roles();
+succession();
+structural_validation();
+assessment_author();
+assessment_time_range();
+resource_def_assessments();
+sharding();
+batched_reads();
 assessment_tray_config();
 assessment_control_registration();
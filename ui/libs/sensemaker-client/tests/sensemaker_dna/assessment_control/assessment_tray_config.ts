@@ -163,7 +163,7 @@ export default () => {
           //@ts-ignore
           console.info(e.message)
           //@ts-ignore
-          t.ok(e.message.match("only the community activator can create this entry"), "only network CA can configure assessment trays; more complex permission structures planned in future");
+          t.ok(e.message.match("only the community activator or a tray curator can create this entry"), "only the network CA or a tray curator can configure assessment trays");
         }
 
 
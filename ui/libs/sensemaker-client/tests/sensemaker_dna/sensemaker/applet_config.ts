@@ -4,6 +4,7 @@ import {
   AppletConfig,
   AppletConfigInput,
   ConfigCulturalContext,
+  ConfigProblem,
   ConfigMethod,
   ConfigResourceDef,
   ConfigThreshold,
@@ -264,6 +265,63 @@ export default () =>
                     cultural_contexts: [configCulturalContext],
                 }
 
+                // a malformed config is rejected with every problem, and nothing is registered
+                const malformedAppletConfigInput: AppletConfigInput = {
+                    name: appletConfig.name,
+                    applet_eh,
+                    ranges: [integerRange, integerRange, integerRange2],
+                    dimensions: [
+                        configDimension,
+                        configObjectiveDimension,
+                        { name: "orphan", range: { name: "orphan-scale", kind: { Integer: { min: 0, max: 5 } } }, computed: false },
+                    ],
+                    resource_defs: [configResourceDef],
+                    methods: [{ ...configMethod, output_dimension: configDimension }],
+                    cultural_contexts: [{
+                        ...configCulturalContext,
+                        thresholds: [{ ...configThreshold, dimension: { name: "missing", range: integerRange2, computed: true } }],
+                        order_by: [[configDimension, { Biggest: null }]],
+                    }],
+                }
+                try {
+                    await callZomeAlice(
+                        "sensemaker",
+                        "register_applet",
+                        malformedAppletConfigInput,
+                        true
+                    );
+                    t.ok(null, "malformed applet config should be rejected");
+                } catch (e) {
+                    const error = JSON.stringify(e);
+                    t.ok(error.includes("more than one range is named 1-scale"));
+                    t.ok(error.includes("dimension orphan has a range not found in root ranges"));
+                    t.ok(error.includes("method total_importance_method has subjective output dimension importance"));
+                    t.ok(error.includes("cultural context most_important_tasks has a threshold on dimension missing"));
+                    t.ok(error.includes("cultural context most_important_tasks orders by subjective dimension importance"));
+                }
+
+                const problems: ConfigProblem[] = await callZomeAlice(
+                    "sensemaker",
+                    "check_applet_config",
+                    malformedAppletConfigInput,
+                    true
+                );
+                t.deepEqual(problems.filter(problem => !("InvalidProgram" in problem)), [
+                    { DuplicateName: { kind: "range", name: "1-scale" } },
+                    { UnknownRange: { dimension: "orphan" } },
+                    { SubjectiveOutputDimension: { method: "total_importance_method", dimension: "importance" } },
+                    { DanglingThresholdDimension: { context: "most_important_tasks", dimension: "missing" } },
+                    { SubjectiveOrderByDimension: { context: "most_important_tasks", dimension: "importance" } },
+                ]);
+
+                const noProblems: ConfigProblem[] = await callZomeAlice(
+                    "sensemaker",
+                    "check_applet_config",
+                    { ...malformedAppletConfigInput, ranges: [integerRange, integerRange2], dimensions: [configDimension, configObjectiveDimension], methods: [configMethod], cultural_contexts: [configCulturalContext] },
+                    true
+                );
+                t.deepEqual(noProblems, []);
+
                 let maybeAppletConfig: any = await callZomeAlice(
                     "sensemaker",
                     "check_if_applet_config_exists",
@@ -280,12 +338,11 @@ export default () =>
                 );
                 t.ok(returnedAppletConfig);
 
-                // We cannot do a serialised comparison as we need to test that config dimensions don't get created
                 // If we test the entry hashes (Object.values of each hash map) then we know the entries are the same
                 t.equal(returnedAppletConfig.name, appletConfig.name);
                 t.equal(encodeHashToBase64(returnedAppletConfig.applet_eh), encodeHashToBase64(appletConfig.applet_eh));
                 t.deepEqual(Object.values(returnedAppletConfig.ranges).map(hash => encodeHashToBase64(hash as Uint8Array)), Object.values(appletConfig.ranges).map(hash => encodeHashToBase64(hash as Uint8Array)));
-                t.equal(Object.values(returnedAppletConfig.dimensions).length, 0); // Dimensions are no longer created from config
+                t.deepEqual(Object.values(returnedAppletConfig.dimensions).map(hash => encodeHashToBase64(hash as Uint8Array)), Object.values(appletConfig.dimensions).map(hash => encodeHashToBase64(hash as Uint8Array)));
                 t.deepEqual(Object.values(returnedAppletConfig.resource_defs).map(hash => encodeHashToBase64(hash as Uint8Array)), Object.values(appletConfig.resource_defs).map(hash => encodeHashToBase64(hash as Uint8Array)));
                 t.deepEqual(Object.values(returnedAppletConfig.methods).map(hash => encodeHashToBase64(hash as Uint8Array)), Object.values(appletConfig.methods).map(hash => encodeHashToBase64(hash as Uint8Array)));
                 t.deepEqual(Object.values(returnedAppletConfig.cultural_contexts).map(hash => encodeHashToBase64(hash as Uint8Array)), Object.values(appletConfig.cultural_contexts).map(hash => encodeHashToBase64(hash as Uint8Array)));
@@ -298,15 +355,269 @@ export default () =>
                 );
                 t.ok(maybeAppletConfig);
                 
-                // We cannot do a serialised comparison as we need to test that config dimensions don't get created
                 // If we test the entry hashes (Object.values of each hash map) then we know the entries are the same
                 t.equal(maybeAppletConfig.name, appletConfig.name);
                 t.equal(encodeHashToBase64(maybeAppletConfig.applet_eh), encodeHashToBase64(appletConfig.applet_eh));
                 t.deepEqual(Object.values(maybeAppletConfig.ranges).map(hash => encodeHashToBase64(hash as Uint8Array)), Object.values(appletConfig.ranges).map(hash => encodeHashToBase64(hash as Uint8Array)));
-                t.equal(Object.values(maybeAppletConfig.dimensions).length, 0); // Dimensions are no longer created from config
+                t.deepEqual(Object.values(maybeAppletConfig.dimensions).map(hash => encodeHashToBase64(hash as Uint8Array)), Object.values(appletConfig.dimensions).map(hash => encodeHashToBase64(hash as Uint8Array)));
                 t.deepEqual(Object.values(maybeAppletConfig.resource_defs).map(hash => encodeHashToBase64(hash as Uint8Array)), Object.values(appletConfig.resource_defs).map(hash => encodeHashToBase64(hash as Uint8Array)));
                 t.deepEqual(Object.values(maybeAppletConfig.methods).map(hash => encodeHashToBase64(hash as Uint8Array)), Object.values(appletConfig.methods).map(hash => encodeHashToBase64(hash as Uint8Array)));
                 t.deepEqual(Object.values(maybeAppletConfig.cultural_contexts).map(hash => encodeHashToBase64(hash as Uint8Array)), Object.values(appletConfig.cultural_contexts).map(hash => encodeHashToBase64(hash as Uint8Array)));
+
+                // updating the config only creates the new entries and records the next version
+                t.equal(returnedAppletConfig.version, 1);
+                const unchangedAppletConfig: AppletConfig = await callZomeAlice(
+                    "sensemaker",
+                    "update_applet_config",
+                    appletConfigInput,
+                    true
+                );
+                t.equal(unchangedAppletConfig.version, 1);
+
+                const extraRange: Range = {
+                    name: "5-scale",
+                    kind: { Integer: { min: 0, max: 5 } },
+                };
+                const extraDimension = {
+                    name: "urgency",
+                    range: extraRange,
+                    computed: false,
+                }
+                const updatedAppletConfig: AppletConfig = await callZomeAlice(
+                    "sensemaker",
+                    "update_applet_config",
+                    {
+                        ...appletConfigInput,
+                        ranges: [...appletConfigInput.ranges!, extraRange],
+                        dimensions: [...appletConfigInput.dimensions!, extraDimension],
+                        methods: [{ ...configMethod, can_compute_live: true }],
+                    },
+                    true
+                );
+                t.equal(updatedAppletConfig.version, 2);
+                t.ok(updatedAppletConfig.previous_config_eh);
+                t.deepEqual(Object.keys(updatedAppletConfig.ranges).sort(), ["1-scale", "1-scale-total", "5-scale"]);
+                t.equal(encodeHashToBase64(updatedAppletConfig.ranges["1-scale"]), encodeHashToBase64(rangeHash));
+                t.equal(encodeHashToBase64(updatedAppletConfig.dimensions["importance"]), encodeHashToBase64(dimensionHash));
+                t.equal(encodeHashToBase64(updatedAppletConfig.cultural_contexts["most_important_tasks"]), encodeHashToBase64(contextEh));
+                const urgencyDimension: Record = await callZomeAlice(
+                    "sensemaker",
+                    "get_dimension",
+                    updatedAppletConfig.dimensions["urgency"],
+                    true
+                );
+                t.equal(new EntryRecord<Dimension>(urgencyDimension).entry.name, "urgency");
+
+                // the replaced method is no longer linked, so only its new version is listed and run live
+                await pause(pauseDuration);
+                const updatedMethodEh = updatedAppletConfig.methods[methodName];
+                t.notEqual(encodeHashToBase64(updatedMethodEh), encodeHashToBase64(methodEh));
+                for (const query of [
+                    { dimension_type: "input", dimension_eh: dimensionHash },
+                    { dimension_type: "output", dimension_eh: objectiveDimensionHash },
+                ]) {
+                    const dimensionMethods: Record[] = await callZomeAlice(
+                        "sensemaker",
+                        "get_methods_for_dimension",
+                        { query },
+                        true
+                    );
+                    t.deepEqual(
+                        dimensionMethods.map((record) => encodeHashToBase64(new EntryRecord<Method>(record).entryHash)),
+                        [encodeHashToBase64(updatedMethodEh)]
+                    );
+                }
+                const allMethods: Record[] = await callZomeAlice("sensemaker", "get_methods", null, true);
+                t.deepEqual(
+                    allMethods.map((record) => encodeHashToBase64(new EntryRecord<Method>(record).entryHash)),
+                    [encodeHashToBase64(updatedMethodEh)]
+                );
+
+                const latestAppletConfig: AppletConfig = await callZomeAlice(
+                    "sensemaker",
+                    "check_if_applet_config_exists",
+                    appletConfigInput.name,
+                    true
+                );
+                t.equal(latestAppletConfig.version, 2);
+
+                const firstAppletConfig: AppletConfig = await callZomeAlice(
+                    "sensemaker",
+                    "get_applet_config_at_version",
+                    { name: appletConfigInput.name, version: 1 },
+                    true
+                );
+                t.equal(firstAppletConfig.version, 1);
+                t.deepEqual(Object.keys(firstAppletConfig.ranges).sort(), ["1-scale", "1-scale-total"]);
+                const futureAppletConfig = await callZomeAlice(
+                    "sensemaker",
+                    "get_applet_config_at_version",
+                    { name: appletConfigInput.name, version: 3 },
+                    true
+                );
+                t.notOk(futureAppletConfig);
+
+                try {
+                    await callZomeAlice(
+                        "sensemaker",
+                        "update_applet_config",
+                        { ...appletConfigInput, name: "unregistered" },
+                        true
+                    );
+                    t.ok(null, "updating an unregistered applet config should fail");
+                } catch (e) {
+                    t.ok(JSON.stringify(e).includes("applet config unregistered is not registered"));
+                }
+
+                // registered configs can be listed and found from their resource defs
+                const appletConfigRecords: Record[] = await callZomeAlice(
+                    "sensemaker",
+                    "get_applet_configs",
+                    null,
+                    true
+                );
+                t.equal(appletConfigRecords.length, 1);
+                const listedAppletConfig = new EntryRecord<AppletConfig>(appletConfigRecords[0]);
+                t.ok(listedAppletConfig.actionHash);
+                t.equal(listedAppletConfig.entry.name, appletConfigInput.name);
+                t.equal(listedAppletConfig.entry.version, 2);
+
+                const resourceDefAppletConfigRecord: Record = await callZomeAlice(
+                    "sensemaker",
+                    "get_applet_config_for_resource_def",
+                    resourceDefEh,
+                    true
+                );
+                const resourceDefAppletConfig = new EntryRecord<AppletConfig>(resourceDefAppletConfigRecord);
+                t.equal(encodeHashToBase64(resourceDefAppletConfig.actionHash), encodeHashToBase64(listedAppletConfig.actionHash));
+                t.equal(resourceDefAppletConfig.entry.version, 2);
+
+                const unknownResourceDefAppletConfig = await callZomeAlice(
+                    "sensemaker",
+                    "get_applet_config_for_resource_def",
+                    await fakeEntryHash(),
+                    true
+                );
+                t.notOk(unknownResourceDefAppletConfig);
+
+                // unregistering removes the applet's config, entries and assessments from the read APIs
+                const beforeAssessments = Date.now() * 1000;
+                const assessmentRecord: Record = await callZomeAlice(
+                    "sensemaker",
+                    "create_assessment",
+                    {
+                        value: { Integer: 1 },
+                        dimension_eh: dimensionHash,
+                        resource_eh: await fakeEntryHash(),
+                        resource_def_eh: resourceDefEh,
+                        maybe_input_dataset: null,
+                    },
+                    true
+                );
+                const assessmentEh = new EntryRecord<any>(assessmentRecord).entryHash;
+                // only bob can delete his assessment, unregistering leaves it unlinked
+                await callZomeBob(
+                    "sensemaker",
+                    "create_assessment",
+                    {
+                        value: { Integer: 2 },
+                        dimension_eh: dimensionHash,
+                        resource_eh: await fakeEntryHash(),
+                        resource_def_eh: resourceDefEh,
+                        maybe_input_dataset: null,
+                    },
+                    true
+                );
+                await pause(pauseDuration);
+
+                try {
+                    await callZomeBob(
+                        "sensemaker",
+                        "unregister_applet",
+                        { applet_name: appletConfigInput.name, keep_assessments: false },
+                        true
+                    );
+                    t.ok(null, "only the community activator or a steward should be able to unregister an applet");
+                } catch (e) {
+                    t.ok(JSON.stringify(e).includes("only the community activator or a steward can unregister an applet"));
+                }
+
+                await callZomeAlice(
+                    "sensemaker",
+                    "unregister_applet",
+                    { applet_name: appletConfigInput.name, keep_assessments: false },
+                    true
+                );
+                await pause(pauseDuration);
+
+                const unregisteredAppletConfig = await callZomeAlice(
+                    "sensemaker",
+                    "check_if_applet_config_exists",
+                    appletConfigInput.name,
+                    true
+                );
+                t.notOk(unregisteredAppletConfig);
+                const remainingAppletConfigs: Record[] = await callZomeAlice(
+                    "sensemaker",
+                    "get_applet_configs",
+                    null,
+                    true
+                );
+                t.equal(remainingAppletConfigs.length, 0);
+                const remainingContexts: Record[] = await callZomeAlice(
+                    "sensemaker",
+                    "get_cultural_contexts",
+                    null,
+                    true
+                );
+                t.equal(remainingContexts.length, 0);
+                const deletedDimension = await callZomeAlice(
+                    "sensemaker",
+                    "get_dimension",
+                    dimensionHash,
+                    true
+                );
+                t.notOk(deletedDimension);
+                const deletedAssessment = await callZomeAlice(
+                    "sensemaker",
+                    "get_assessment",
+                    assessmentEh,
+                    true
+                );
+                t.notOk(deletedAssessment);
+                const bobsUnlinkedAssessments = await callZomeAlice(
+                    "sensemaker",
+                    "get_assessments_by_agent",
+                    { agent: bob_agent_key, dimension_ehs: null, resource_def_ehs: null },
+                    true
+                );
+                t.deepEqual(bobsUnlinkedAssessments, []);
+                const unlinkedAssessmentsInTimeRange = await callZomeAlice(
+                    "sensemaker",
+                    "get_assessments_in_time_range",
+                    { resource_def_eh: resourceDefEh, from: beforeAssessments, to: Date.now() * 1000, dimension_ehs: null },
+                    true
+                );
+                t.deepEqual(unlinkedAssessmentsInTimeRange, []);
+                const unlinkedResourceDefAssessments = await callZomeAlice(
+                    "sensemaker",
+                    "get_assessments_for_resources",
+                    { resource_ehs: null, dimension_ehs: null, resource_def_ehs: [resourceDefEh] },
+                    true
+                );
+                t.deepEqual(unlinkedResourceDefAssessments, {});
+
+                try {
+                    await callZomeAlice(
+                        "sensemaker",
+                        "unregister_applet",
+                        { applet_name: appletConfigInput.name, keep_assessments: false },
+                        true
+                    );
+                    t.ok(null, "unregistering an applet twice should fail");
+                } catch (e) {
+                    t.ok(JSON.stringify(e).includes("applet config todo is not registered"));
+                }
             } catch (e) {
                 console.log(e);
                 t.ok(null);
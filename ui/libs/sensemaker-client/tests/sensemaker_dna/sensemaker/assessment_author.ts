@@ -0,0 +1,163 @@
+import { fakeEntryHash } from "@holochain/client";
+import { pause, runScenario } from "@holochain/tryorama";
+import { Assessment, Dimension, Range, ResourceDef } from "#client";
+import pkg from "tape-promise/tape";
+import { setUpAliceandBob } from "../../utils";
+import { EntryRecord } from "@holochain-open-dev/utils";
+const { test } = pkg;
+
+export default () => {
+  test("assessment authorship and author index", async (t) => {
+    await runScenario(async (scenario) => {
+      const {
+        alice,
+        bob,
+        cleanup,
+        alice_agent_key,
+        bob_agent_key,
+        ss_cell_id_alice,
+        ss_cell_id_bob,
+      } = await setUpAliceandBob();
+
+      const callZomeAlice = async (fn_name, payload) => {
+        return await alice.callZome({
+          cap_secret: null,
+          cell_id: ss_cell_id_alice,
+          zome_name: "sensemaker",
+          fn_name,
+          payload,
+          provenance: alice_agent_key,
+        });
+      };
+      const callZomeBob = async (fn_name, payload) => {
+        return await bob.callZome({
+          cap_secret: null,
+          cell_id: ss_cell_id_bob,
+          zome_name: "sensemaker",
+          fn_name,
+          payload,
+          provenance: bob_agent_key,
+        });
+      };
+      const expectInvalid = async (call: Promise<any>, error: string) => {
+        try {
+          await call;
+          t.ok(null, `should be rejected with: ${error}`);
+        } catch (e) {
+          t.ok(JSON.stringify(e).includes(error), error);
+        }
+      };
+      try {
+        const pauseDuration = 1000;
+        await scenario.shareAllAgents();
+        await pause(pauseDuration);
+
+        const rangeHash = new EntryRecord<Range>(
+          await callZomeAlice("create_range", { name: "10-scale", kind: { Integer: { min: 0, max: 10 } } })
+        ).entryHash;
+        const dimensionHash = new EntryRecord<Dimension>(
+          await callZomeAlice("create_dimension", { name: "likeness", range_eh: rangeHash, computed: false })
+        ).entryHash;
+        const resourceDefHash = new EntryRecord<ResourceDef>(
+          await callZomeAlice("create_resource_def", {
+            resource_name: "post",
+            applet_eh: await fakeEntryHash(),
+            base_types: [{ entry_index: 0, zome_index: 0, visibility: { Public: null } }],
+            role_name: "test_provider_dna",
+            zome_name: "provider",
+          })
+        ).entryHash;
+        const assessmentRecord = new EntryRecord<Assessment>(
+          await callZomeAlice("create_assessment", {
+            value: { Integer: 4 },
+            dimension_eh: dimensionHash,
+            resource_eh: await fakeEntryHash(),
+            resource_def_eh: resourceDefHash,
+            maybe_input_dataset: null,
+          })
+        );
+        t.deepEqual(assessmentRecord.entry.author, alice_agent_key);
+
+        // assessments are indexed by their author, and can be filtered by dimension and resource def
+        const otherDimensionHash = new EntryRecord<Dimension>(
+          await callZomeAlice("create_dimension", { name: "importance", range_eh: rangeHash, computed: false })
+        ).entryHash;
+        await pause(pauseDuration);
+        await callZomeAlice("create_assessment", {
+          value: { Integer: 7 },
+          dimension_eh: otherDimensionHash,
+          resource_eh: assessmentRecord.entry.resource_eh,
+          resource_def_eh: resourceDefHash,
+          maybe_input_dataset: null,
+        });
+        await callZomeBob("create_assessment", {
+          value: { Integer: 2 },
+          dimension_eh: dimensionHash,
+          resource_eh: assessmentRecord.entry.resource_eh,
+          resource_def_eh: resourceDefHash,
+          maybe_input_dataset: null,
+        });
+        await pause(pauseDuration);
+
+        const myAssessments: Assessment[] = await callZomeAlice("get_my_assessments", {});
+        t.equal(myAssessments.length, 2);
+        t.ok(myAssessments.every((assessment) => assessment.author.toString() === alice_agent_key.toString()));
+        const myLikenessAssessments: Assessment[] = await callZomeAlice("get_my_assessments", { dimension_ehs: [dimensionHash] });
+        t.deepEqual(myLikenessAssessments.map((assessment) => assessment.value), [{ Integer: 4 }]);
+        const bobsAssessments: Assessment[] = await callZomeAlice("get_assessments_by_agent", { agent: bob_agent_key });
+        t.deepEqual(bobsAssessments.map((assessment) => assessment.value), [{ Integer: 2 }]);
+        const bobsAssessmentsOnOtherResourceDefs: Assessment[] = await callZomeAlice("get_assessments_by_agent", {
+          agent: bob_agent_key,
+          resource_def_ehs: [await fakeEntryHash()],
+        });
+        t.equal(bobsAssessmentsOnOtherResourceDefs.length, 0);
+
+        const now = () => Date.now() * 1000;
+        const updatedAssessment = { ...assessmentRecord.entry, value: { Integer: 5 }, timestamp: now() };
+
+        // Bob can neither update nor delete Alice's assessment
+        await expectInvalid(
+          callZomeBob("update_assessment", {
+            original_action_hash: assessmentRecord.actionHash,
+            updated_assessment: { ...updatedAssessment, author: bob_agent_key },
+          }),
+          "assessments can only be updated or deleted by their author"
+        );
+        await expectInvalid(
+          callZomeBob("delete_assessment", assessmentRecord.actionHash),
+          "assessments can only be updated or deleted by their author"
+        );
+
+        // Alice can't claim Bob as the author or backdate her assessment
+        await expectInvalid(
+          callZomeAlice("update_assessment", {
+            original_action_hash: assessmentRecord.actionHash,
+            updated_assessment: { ...updatedAssessment, author: bob_agent_key },
+          }),
+          "an assessment's author must be the agent committing it"
+        );
+        await expectInvalid(
+          callZomeAlice("update_assessment", {
+            original_action_hash: assessmentRecord.actionHash,
+            updated_assessment: { ...updatedAssessment, timestamp: now() - 24 * 60 * 60 * 1000 * 1000 },
+          }),
+          "an assessment's timestamp must be within 5 minutes of the action committing it"
+        );
+
+        // but she can update and delete her own
+        t.ok(
+          await callZomeAlice("update_assessment", {
+            original_action_hash: assessmentRecord.actionHash,
+            updated_assessment: { ...updatedAssessment, timestamp: now() },
+          })
+        );
+        t.ok(await callZomeAlice("delete_assessment", assessmentRecord.actionHash));
+      } catch (e) {
+        console.log(e);
+        t.ok(null);
+      }
+
+      await cleanup();
+    });
+  });
+};
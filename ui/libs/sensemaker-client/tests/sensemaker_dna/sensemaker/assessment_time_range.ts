@@ -0,0 +1,101 @@
+import { fakeEntryHash } from "@holochain/client";
+import { pause, runScenario } from "@holochain/tryorama";
+import { Assessment, Dimension, Range, ResourceDef } from "#client";
+import pkg from "tape-promise/tape";
+import { setUpAliceandBob } from "../../utils";
+import { EntryRecord } from "@holochain-open-dev/utils";
+const { test } = pkg;
+
+const HOUR = 60 * 60 * 1000 * 1000;
+const DAY = 24 * HOUR;
+
+export default () => {
+  test("assessments in a time range", async (t) => {
+    await runScenario(async (scenario) => {
+      const { alice, cleanup, alice_agent_key, ss_cell_id_alice } = await setUpAliceandBob();
+
+      const callZomeAlice = async (fn_name, payload) => {
+        return await alice.callZome({
+          cap_secret: null,
+          cell_id: ss_cell_id_alice,
+          zome_name: "sensemaker",
+          fn_name,
+          payload,
+          provenance: alice_agent_key,
+        });
+      };
+      try {
+        const pauseDuration = 1000;
+        await scenario.shareAllAgents();
+        await pause(pauseDuration);
+
+        const rangeHash = new EntryRecord<Range>(
+          await callZomeAlice("create_range", { name: "10-scale", kind: { Integer: { min: 0, max: 10 } } })
+        ).entryHash;
+        const likenessDimensionHash = new EntryRecord<Dimension>(
+          await callZomeAlice("create_dimension", { name: "likeness", range_eh: rangeHash, computed: false })
+        ).entryHash;
+        const importanceDimensionHash = new EntryRecord<Dimension>(
+          await callZomeAlice("create_dimension", { name: "importance", range_eh: rangeHash, computed: false })
+        ).entryHash;
+        const resourceDefHash = new EntryRecord<ResourceDef>(
+          await callZomeAlice("create_resource_def", {
+            resource_name: "post",
+            applet_eh: await fakeEntryHash(),
+            base_types: [{ entry_index: 0, zome_index: 0, visibility: { Public: null } }],
+            role_name: "test_provider_dna",
+            zome_name: "provider",
+          })
+        ).entryHash;
+        await pause(pauseDuration);
+
+        const before = Date.now() * 1000;
+        const resource_eh = await fakeEntryHash();
+        for (const [dimension_eh, value] of [[likenessDimensionHash, 3], [importanceDimensionHash, 8]]) {
+          await callZomeAlice("create_assessment", {
+            value: { Integer: value },
+            dimension_eh,
+            resource_eh,
+            resource_def_eh: resourceDefHash,
+            maybe_input_dataset: null,
+          });
+        }
+        const after = Date.now() * 1000 + 1;
+        await pause(pauseDuration);
+
+        const inTimeRange = async (from: number, to: number, dimension_ehs?): Promise<Assessment[]> =>
+          await callZomeAlice("get_assessments_in_time_range", { resource_def_eh: resourceDefHash, from, to, dimension_ehs });
+
+        // a window within the hour, and one spanning whole days, both find the assessments
+        t.equal((await inTimeRange(before, after)).length, 2);
+        t.equal((await inTimeRange(before - 3 * DAY, after + 3 * DAY)).length, 2);
+
+        // windows ending before or starting after the assessments find nothing
+        t.equal((await inTimeRange(before - HOUR, before)).length, 0);
+        t.equal((await inTimeRange(after, after + HOUR)).length, 0);
+
+        // and the dimension filter is applied
+        const likenessAssessments = await inTimeRange(before - DAY, after + DAY, [likenessDimensionHash]);
+        t.deepEqual(likenessAssessments.map((assessment) => assessment.value), [{ Integer: 3 }]);
+
+        try {
+          await inTimeRange(after, before);
+          t.ok(null, "a window ending before it starts should be rejected");
+        } catch (e) {
+          t.ok(JSON.stringify(e).includes("the time range must not end before it starts"));
+        }
+        try {
+          await inTimeRange(before - 32 * DAY, after);
+          t.ok(null, "a window longer than 31 days should be rejected");
+        } catch (e) {
+          t.ok(JSON.stringify(e).includes("the time range can span at most 31 days"));
+        }
+      } catch (e) {
+        console.log(e);
+        t.ok(null);
+      }
+
+      await cleanup();
+    });
+  });
+};
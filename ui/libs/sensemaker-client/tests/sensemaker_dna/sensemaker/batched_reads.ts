@@ -0,0 +1,125 @@
+import { EntryHash, encodeHashToBase64, fakeEntryHash } from "@holochain/client";
+import { pause, runScenario } from "@holochain/tryorama";
+import { Assessment, CulturalContext, Dimension, Range, ResourceDef } from "#client";
+import pkg from "tape-promise/tape";
+import { setUpAliceandBob } from "../../utils";
+import { EntryRecord } from "@holochain-open-dev/utils";
+const { test } = pkg;
+
+export default () => {
+  test("batched reads over many resources", async (t) => {
+    await runScenario(async (scenario) => {
+      const {
+        alice,
+        bob,
+        cleanup,
+        alice_agent_key,
+        bob_agent_key,
+        ss_cell_id_alice,
+        ss_cell_id_bob,
+      } = await setUpAliceandBob();
+
+      const callZomeAlice = async (fn_name, payload) => {
+        return await alice.callZome({
+          cap_secret: null,
+          cell_id: ss_cell_id_alice,
+          zome_name: "sensemaker",
+          fn_name,
+          payload,
+          provenance: alice_agent_key,
+        });
+      };
+      const callZomeBob = async (fn_name, payload) => {
+        return await bob.callZome({
+          cap_secret: null,
+          cell_id: ss_cell_id_bob,
+          zome_name: "sensemaker",
+          fn_name,
+          payload,
+          provenance: bob_agent_key,
+        });
+      };
+      try {
+        const pauseDuration = 1000;
+        await scenario.shareAllAgents();
+        await pause(pauseDuration);
+
+        const rangeHash = new EntryRecord<Range>(
+          await callZomeAlice("create_range", { name: "10-scale", kind: { Integer: { min: 0, max: 10 } } })
+        ).entryHash;
+        const likenessDimensionHash = new EntryRecord<Dimension>(
+          await callZomeAlice("create_dimension", { name: "total_likeness", range_eh: rangeHash, computed: true })
+        ).entryHash;
+        const resourceDefHash = new EntryRecord<ResourceDef>(
+          await callZomeAlice("create_resource_def", {
+            resource_name: "post",
+            applet_eh: await fakeEntryHash(),
+            base_types: [{ entry_index: 0, zome_index: 0, visibility: { Public: null } }],
+            role_name: "test_provider_dna",
+            zome_name: "provider",
+          })
+        ).entryHash;
+
+        // resource i is assessed with i
+        const before = Date.now() * 1000;
+        const resourceHashes: EntryHash[] = [];
+        for (let i = 0; i <= 10; i++) {
+          const resource_eh = await fakeEntryHash();
+          resourceHashes.push(resource_eh);
+          await callZomeAlice("create_assessment", {
+            value: { Integer: i },
+            dimension_eh: likenessDimensionHash,
+            resource_eh,
+            resource_def_eh: resourceDefHash,
+            maybe_input_dataset: null,
+          });
+        }
+        const unassessedResourceHash = await fakeEntryHash();
+
+        const contextHash = new EntryRecord<CulturalContext>(
+          await callZomeAlice("create_cultural_context", {
+            name: "liked",
+            resource_def_eh: resourceDefHash,
+            thresholds: [{ dimension_eh: likenessDimensionHash, kind: { GreaterThan: null }, value: { Integer: 6 } }],
+            order_by: [[likenessDimensionHash, { Biggest: null }]],
+          })
+        ).entryHash;
+        await pause(pauseDuration);
+
+        // a resource passed twice is only ranked once, and a resource without assessments is left out
+        for (const callZome of [callZomeAlice, callZomeBob]) {
+          const contextResult: EntryHash[] = await callZome("compute_context", {
+            resource_ehs: [...resourceHashes, unassessedResourceHash, resourceHashes[10]],
+            context_eh: contextHash,
+            can_publish_result: false,
+          });
+          t.deepEqual(contextResult, [resourceHashes[10], resourceHashes[9], resourceHashes[8], resourceHashes[7]]);
+        }
+
+        // every requested resource is in the result, with no assessments if it has none
+        const assessmentsForResources: Record<string, Assessment[]> = await callZomeBob("get_assessments_for_resources", {
+          resource_ehs: [resourceHashes[3], unassessedResourceHash],
+          dimension_ehs: [likenessDimensionHash],
+        });
+        t.deepEqual(
+          assessmentsForResources[encodeHashToBase64(resourceHashes[3])].map((assessment) => assessment.value),
+          [{ Integer: 3 }]
+        );
+        t.deepEqual(assessmentsForResources[encodeHashToBase64(unassessedResourceHash)], []);
+
+        const likedInTimeRange: Assessment[] = await callZomeBob("get_assessments_in_time_range", {
+          resource_def_eh: resourceDefHash,
+          from: before,
+          to: Date.now() * 1000 + 1,
+          dimension_ehs: [likenessDimensionHash],
+        });
+        t.equal(likedInTimeRange.length, resourceHashes.length);
+      } catch (e) {
+        console.log(e);
+        t.ok(null);
+      }
+
+      await cleanup();
+    });
+  });
+};
@@ -0,0 +1,240 @@
+import { EntryHash, Record, fakeEntryHash } from "@holochain/client";
+import { pause, runScenario } from "@holochain/tryorama";
+import {
+  Assessment,
+  CreateAssessmentInput,
+  Dimension,
+  Method,
+  Range,
+  ResourceDef,
+} from "#client";
+import pkg from "tape-promise/tape";
+import { setUpAliceandBob } from "../../utils";
+import { EntryRecord } from "@holochain-open-dev/utils";
+const { test } = pkg;
+
+export default () => {
+  test("categorical range methods", async (t) => {
+    await runScenario(async (scenario) => {
+      const {
+        alice,
+        cleanup,
+        alice_agent_key,
+        ss_cell_id_alice,
+        provider_cell_id_alice,
+      } = await setUpAliceandBob();
+
+      const callZomeAlice = async (
+        zome_name,
+        fn_name,
+        payload,
+        is_ss = false
+      ) => {
+        return await alice.callZome({
+          cap_secret: null,
+          cell_id: is_ss ? ss_cell_id_alice : provider_cell_id_alice,
+          zome_name,
+          fn_name,
+          payload,
+          provenance: alice_agent_key,
+        });
+      };
+      try {
+        const pauseDuration = 1000;
+        await scenario.shareAllAgents();
+        await pause(pauseDuration);
+
+        const applet_eh = await fakeEntryHash();
+
+        const createPostEntryHash: EntryHash = await callZomeAlice(
+          "test_provider",
+          "create_post",
+          { title: "Intro", content: "buy my stuff" }
+        );
+        t.ok(createPostEntryHash);
+
+        const moderationRange: Range = {
+          name: "moderation",
+          kind: {
+            Tag: ["helpful", "off-topic", "spam"],
+          },
+        };
+        const countRange: Range = {
+          name: "count",
+          kind: {
+            Integer: { min: 0, max: 1000000 },
+          },
+        };
+        const moderationRangeHash = new EntryRecord<Range>(
+          await callZomeAlice("sensemaker", "create_range", moderationRange, true)
+        ).entryHash;
+        const countRangeHash = new EntryRecord<Range>(
+          await callZomeAlice("sensemaker", "create_range", countRange, true)
+        ).entryHash;
+
+        const moderationDimensionHash = new EntryRecord<Dimension>(
+          await callZomeAlice(
+            "sensemaker",
+            "create_dimension",
+            { name: "moderation", range_eh: moderationRangeHash, computed: false },
+            true
+          )
+        ).entryHash;
+        const spamCountDimensionHash = new EntryRecord<Dimension>(
+          await callZomeAlice(
+            "sensemaker",
+            "create_dimension",
+            { name: "spam_count", range_eh: countRangeHash, computed: true },
+            true
+          )
+        ).entryHash;
+        const consensusDimensionHash = new EntryRecord<Dimension>(
+          await callZomeAlice(
+            "sensemaker",
+            "create_dimension",
+            { name: "moderation_consensus", range_eh: moderationRangeHash, computed: true },
+            true
+          )
+        ).entryHash;
+
+        const createResourceDef: ResourceDef = {
+          resource_name: "post",
+          applet_eh,
+          //@ts-ignore
+          base_types: [
+            //@ts-ignore
+            { entry_index: 0, zome_index: 0, visibility: { Public: null } },
+          ],
+          role_name: "test_provider_dna",
+          zome_name: "provider",
+        };
+        const resourceDefHash = new EntryRecord<ResourceDef>(
+          await callZomeAlice("sensemaker", "create_resource_def", createResourceDef, true)
+        ).entryHash;
+
+        for (const tag of ["spam", "spam", "helpful"]) {
+          const createAssessment: CreateAssessmentInput = {
+            value: { Tag: tag },
+            dimension_eh: moderationDimensionHash,
+            resource_eh: createPostEntryHash,
+            resource_def_eh: resourceDefHash,
+            maybe_input_dataset: null,
+          };
+          const assessmentRecord: Record = await callZomeAlice(
+            "sensemaker",
+            "create_assessment",
+            createAssessment,
+            true
+          );
+          t.ok(assessmentRecord);
+        }
+
+        // Alice assesses with a tag that is not in the range but fails
+        try {
+          await callZomeAlice(
+            "sensemaker",
+            "create_assessment",
+            {
+              value: { Tag: "excellent" },
+              dimension_eh: moderationDimensionHash,
+              resource_eh: createPostEntryHash,
+              resource_def_eh: resourceDefHash,
+              maybe_input_dataset: null,
+            },
+            true
+          );
+          t.fail("assessment with a tag outside of the range was committed");
+        } catch (e) {
+          t.ok(JSON.stringify(e).includes("tag excellent is not one of the tags in the range"));
+        }
+
+        // Alice assesses with a value of the wrong kind but fails
+        try {
+          await callZomeAlice(
+            "sensemaker",
+            "create_assessment",
+            {
+              value: { Integer: 9000 },
+              dimension_eh: moderationDimensionHash,
+              resource_eh: createPostEntryHash,
+              resource_def_eh: resourceDefHash,
+              maybe_input_dataset: null,
+            },
+            true
+          );
+          t.fail("assessment of the wrong range kind was committed");
+        } catch (e) {
+          t.ok(JSON.stringify(e).includes("value is not of the same kind as the range"));
+        }
+
+        const spamCountMethodHash = new EntryRecord<Method>(
+          await callZomeAlice(
+            "sensemaker",
+            "create_method",
+            {
+              name: "spam_count_method",
+              input_dimension_ehs: [moderationDimensionHash],
+              output_dimension_eh: spamCountDimensionHash,
+              program: { Count: { Tag: "spam" } },
+              can_compute_live: false,
+              requires_validation: false,
+            },
+            true
+          )
+        ).entryHash;
+        const consensusMethodHash = new EntryRecord<Method>(
+          await callZomeAlice(
+            "sensemaker",
+            "create_method",
+            {
+              name: "moderation_consensus_method",
+              input_dimension_ehs: [moderationDimensionHash],
+              output_dimension_eh: consensusDimensionHash,
+              program: { Mode: null },
+              can_compute_live: false,
+              requires_validation: false,
+            },
+            true
+          )
+        ).entryHash;
+
+        await pause(pauseDuration);
+
+        // When Alice counts the "spam" assessments Then the two spam tags are counted
+        const spamCount: Assessment = new EntryRecord<Assessment>(
+          await callZomeAlice(
+            "sensemaker",
+            "run_method",
+            {
+              resource_eh: createPostEntryHash,
+              resource_def_eh: resourceDefHash,
+              method_eh: spamCountMethodHash,
+            },
+            true
+          )
+        ).entry;
+        t.deepEqual(spamCount.value, { Integer: 2 });
+
+        // When Alice computes the most common tag Then "spam" is returned
+        const consensus: Assessment = new EntryRecord<Assessment>(
+          await callZomeAlice(
+            "sensemaker",
+            "run_method",
+            {
+              resource_eh: createPostEntryHash,
+              resource_def_eh: resourceDefHash,
+              method_eh: consensusMethodHash,
+            },
+            true
+          )
+        ).entry;
+        t.deepEqual(consensus.value, { Tag: "spam" });
+      } catch (e) {
+        console.log(e);
+        t.ok(null);
+      }
+
+      await cleanup();
+    });
+  });
+};
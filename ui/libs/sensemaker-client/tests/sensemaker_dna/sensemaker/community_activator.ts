@@ -7,11 +7,14 @@ import { EntryRecord } from "@holochain-open-dev/utils";
 import { ResourceDef } from "#client";
 const { test } = pkg;
 
-const COMMUNITY_ACTIVATOR_ZOME_ERROR = JSON.stringify({
+// validation names the role the entry needs
+const roleZomeError = (role: string) => JSON.stringify({
   name: "internal_error",
   message:
-    "Source chain error: InvalidCommit error: only the community activator can create this entry",
+    `Source chain error: InvalidCommit error: only the community activator or a ${role} can create this entry`,
 });
+const COMMUNITY_ACTIVATOR_ZOME_ERROR = roleZomeError("steward");
+const METHOD_AUTHOR_ZOME_ERROR = roleZomeError("method author");
 
 export default () => test("test CA progenitor pattern", async (t) => {
     await runScenario(async scenario => {
@@ -97,6 +100,15 @@ export default () => test("test CA progenitor pattern", async (t) => {
         )
         const createDimensionEntryHash = new EntryRecord<Range>(createDimensionRecord).entryHash;
 
+        // Alice creates an objective dimension for the method to output to
+        const createObjectiveDimensionRecord: Record = await callZomeAlice(
+            "sensemaker",
+            "create_dimension",
+            { "name": "total_likeness", "range_eh": rangeHash, "computed": true },
+            true
+        )
+        const createObjectiveDimensionEntryHash = new EntryRecord<Range>(createObjectiveDimensionRecord).entryHash;
+
         // Bob creates a dimension but fails
         try {
             await callZomeBob(
@@ -149,7 +161,7 @@ export default () => test("test CA progenitor pattern", async (t) => {
         const totalLikenessMethod = {
             "name": "total_likeness_method",
             "input_dimension_ehs": [createDimensionEntryHash],
-            "output_dimension_eh": createDimensionEntryHash,
+            "output_dimension_eh": createObjectiveDimensionEntryHash,
             "program": { "Sum": null },
             "can_compute_live": false,
             "requires_validation": false,
@@ -172,10 +184,10 @@ export default () => test("test CA progenitor pattern", async (t) => {
                 true
             )
         } catch (e) {
-            t.deepEqual(JSON.stringify(e), COMMUNITY_ACTIVATOR_ZOME_ERROR);
+            t.deepEqual(JSON.stringify(e), METHOD_AUTHOR_ZOME_ERROR);
 
             const threshold = {
-                "dimension_eh": createDimensionEntryHash,
+                "dimension_eh": createObjectiveDimensionEntryHash,
                 "kind": { "Equal": null },
                 "value": { "Integer": 5 },
             }
@@ -183,7 +195,7 @@ export default () => test("test CA progenitor pattern", async (t) => {
                 "name": "testcontext",
                 "resource_def_eh": createResourceDefEntryHash,
                 "thresholds": [threshold],
-                "order_by": [[createDimensionEntryHash, { "Biggest": null }]], // DimensionEh
+                "order_by": [[createObjectiveDimensionEntryHash, { "Biggest": null }]], // DimensionEh
             }
             try {
                 await callZomeBob(
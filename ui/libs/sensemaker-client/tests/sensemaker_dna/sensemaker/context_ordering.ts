@@ -0,0 +1,427 @@
+import { EntryHash, Record, encodeHashToBase64, fakeEntryHash } from "@holochain/client";
+import { pause, runScenario } from "@holochain/tryorama";
+import {
+  ContextResult,
+  CreateAssessmentInput,
+  CulturalContext,
+  Dimension,
+  Range,
+  ResourceDef,
+} from "#client";
+import pkg from "tape-promise/tape";
+import { setUpAliceandBob } from "../../utils";
+import { EntryRecord } from "@holochain-open-dev/utils";
+const { test } = pkg;
+
+export default () => {
+  test("cultural context ordering", async (t) => {
+    await runScenario(async (scenario) => {
+      const {
+        alice,
+        bob,
+        cleanup,
+        alice_agent_key,
+        bob_agent_key,
+        ss_cell_id_alice,
+        ss_cell_id_bob,
+        provider_cell_id_alice,
+      } = await setUpAliceandBob();
+
+      const callZomeAlice = async (
+        zome_name,
+        fn_name,
+        payload,
+        is_ss = false
+      ) => {
+        return await alice.callZome({
+          cap_secret: null,
+          cell_id: is_ss ? ss_cell_id_alice : provider_cell_id_alice,
+          zome_name,
+          fn_name,
+          payload,
+          provenance: alice_agent_key,
+        });
+      };
+      const callZomeBob = async (fn_name, payload) => {
+        return await bob.callZome({
+          cap_secret: null,
+          cell_id: ss_cell_id_bob,
+          zome_name: "sensemaker",
+          fn_name,
+          payload,
+          provenance: bob_agent_key,
+        });
+      };
+      try {
+        const pauseDuration = 1000;
+        await scenario.shareAllAgents();
+        await pause(pauseDuration);
+
+        const applet_eh = await fakeEntryHash();
+
+        const createPost = async (title: string): Promise<EntryHash> =>
+          await callZomeAlice("test_provider", "create_post", { title, content: "" });
+        const post1 = await createPost("one");
+        const post2 = await createPost("two");
+        const post3 = await createPost("three");
+        const post4 = await createPost("four");
+
+        const rangeHash = new EntryRecord<Range>(
+          await callZomeAlice(
+            "sensemaker",
+            "create_range",
+            { name: "10-scale", kind: { Integer: { min: 0, max: 10 } } },
+            true
+          )
+        ).entryHash;
+        const createDimension = async (name: string) =>
+          new EntryRecord<Dimension>(
+            await callZomeAlice("sensemaker", "create_dimension", { name, range_eh: rangeHash, computed: true }, true)
+          ).entryHash;
+        const importanceDimensionHash = await createDimension("total_importance");
+        const likenessDimensionHash = await createDimension("total_likeness");
+
+        const createResourceDef: ResourceDef = {
+          resource_name: "post",
+          applet_eh,
+          //@ts-ignore
+          base_types: [
+            //@ts-ignore
+            { entry_index: 0, zome_index: 0, visibility: { Public: null } },
+          ],
+          role_name: "test_provider_dna",
+          zome_name: "provider",
+        };
+        const resourceDefHash = new EntryRecord<ResourceDef>(
+          await callZomeAlice("sensemaker", "create_resource_def", createResourceDef, true)
+        ).entryHash;
+
+        // post1, post2 and post3 tie on importance, post3 has no likeness and post4 has no importance at all
+        const assessments: Array<[EntryHash, EntryHash, number]> = [
+          [post1, importanceDimensionHash, 5],
+          [post1, likenessDimensionHash, 2],
+          [post2, importanceDimensionHash, 5],
+          [post2, likenessDimensionHash, 7],
+          [post3, importanceDimensionHash, 5],
+          [post4, likenessDimensionHash, 10],
+        ];
+        for (const [resource_eh, dimension_eh, value] of assessments) {
+          const createAssessment: CreateAssessmentInput = {
+            value: { Integer: value },
+            dimension_eh,
+            resource_eh,
+            resource_def_eh: resourceDefHash,
+            maybe_input_dataset: null,
+          };
+          t.ok(await callZomeAlice("sensemaker", "create_assessment", createAssessment, true));
+        }
+
+        const contextHash = new EntryRecord<CulturalContext>(
+          await callZomeAlice(
+            "sensemaker",
+            "create_cultural_context",
+            {
+              name: "most important, then most liked",
+              resource_def_eh: resourceDefHash,
+              thresholds: [],
+              order_by: [
+                [importanceDimensionHash, { Biggest: null }],
+                [likenessDimensionHash, { Biggest: null }],
+              ],
+            },
+            true
+          )
+        ).entryHash;
+        await pause(pauseDuration);
+
+        // When Alice computes the context Then likeness breaks the importance tie, unliked posts come last and posts without an importance are left out
+        const contextResult: EntryHash[] = await callZomeAlice(
+          "sensemaker",
+          "compute_context",
+          {
+            resource_ehs: [post1, post2, post3, post4],
+            context_eh: contextHash,
+            can_publish_result: false,
+          },
+          true
+        );
+        t.deepEqual(contextResult, [post2, post1, post3]);
+
+        // When Alice computes the context over the resources in a different order Then the result is the same
+        const reversedContextResult: EntryHash[] = await callZomeAlice(
+          "sensemaker",
+          "compute_context",
+          {
+            resource_ehs: [post4, post3, post2, post1],
+            context_eh: contextHash,
+            can_publish_result: false,
+          },
+          true
+        );
+        t.deepEqual(reversedContextResult, contextResult);
+
+        // When Alice publishes the result Then it can be read back as the context's latest result
+        await callZomeAlice(
+          "sensemaker",
+          "compute_context",
+          {
+            resource_ehs: [post1, post2, post3, post4],
+            context_eh: contextHash,
+            can_publish_result: true,
+          },
+          true
+        );
+        await pause(pauseDuration);
+        const latestContextResult = new EntryRecord<ContextResult>(
+          await callZomeAlice("sensemaker", "get_latest_context_result", contextHash, true)
+        ).entry;
+        t.deepEqual(latestContextResult.dimension_ehs, [importanceDimensionHash, likenessDimensionHash]);
+        t.deepEqual(latestContextResult.result, [
+          [post2, [{ Integer: 5 }, { Integer: 7 }]],
+          [post1, [{ Integer: 5 }, { Integer: 2 }]],
+          [post3, [{ Integer: 5 }, null]],
+        ]);
+
+        // When Bob, who isn't a steward, asks to publish a result Then they still get the ordering, nothing is
+        // published and Alice's result stays the latest
+        const bobContextResult: EntryHash[] = await callZomeBob("compute_context", {
+          resource_ehs: [post1, post2, post3, post4],
+          context_eh: contextHash,
+          can_publish_result: true,
+        });
+        t.deepEqual(bobContextResult, contextResult);
+        await pause(pauseDuration);
+        const stillLatestContextResult = new EntryRecord<ContextResult>(
+          await callZomeAlice("sensemaker", "get_latest_context_result", contextHash, true)
+        );
+        t.equal(encodeHashToBase64(stillLatestContextResult.action.author), encodeHashToBase64(alice_agent_key));
+        t.deepEqual(stillLatestContextResult.entry.result, latestContextResult.result);
+
+        const ratingRangeHash = new EntryRecord<Range>(
+          await callZomeAlice(
+            "sensemaker",
+            "create_range",
+            { name: "rating", kind: { Float: { min: 0, max: 10 } } },
+            true
+          )
+        ).entryHash;
+        const ratingDimensionHash = new EntryRecord<Dimension>(
+          await callZomeAlice(
+            "sensemaker",
+            "create_dimension",
+            { name: "average_rating", range_eh: ratingRangeHash, computed: true },
+            true
+          )
+        ).entryHash;
+        const ratings: Array<[EntryHash, number]> = [
+          [post1, 7.25],
+          [post2, 0.5],
+          [post3, 2.5],
+          [post4, 1],
+        ];
+        for (const [resource_eh, value] of ratings) {
+          t.ok(
+            await callZomeAlice(
+              "sensemaker",
+              "create_assessment",
+              {
+                value: { Float: value },
+                dimension_eh: ratingDimensionHash,
+                resource_eh,
+                resource_def_eh: resourceDefHash,
+                maybe_input_dataset: null,
+              },
+              true
+            )
+          );
+        }
+
+        // Alice assesses with a NaN rating but fails
+        try {
+          await callZomeAlice(
+            "sensemaker",
+            "create_assessment",
+            {
+              value: { Float: NaN },
+              dimension_eh: ratingDimensionHash,
+              resource_eh: post1,
+              resource_def_eh: resourceDefHash,
+              maybe_input_dataset: null,
+            },
+            true
+          );
+          t.fail("assessment with a NaN value was committed");
+        } catch (e) {
+          t.ok(JSON.stringify(e).includes("NaN is not a valid value"));
+        }
+
+        // the Integer threshold is compared with the Float ratings as a float
+        const ratingContextHash = new EntryRecord<CulturalContext>(
+          await callZomeAlice(
+            "sensemaker",
+            "create_cultural_context",
+            {
+              name: "rated above 0, lowest first",
+              resource_def_eh: resourceDefHash,
+              thresholds: [
+                {
+                  dimension_eh: ratingDimensionHash,
+                  kind: { GreaterThan: null },
+                  value: { Integer: 0 },
+                },
+              ],
+              order_by: [[ratingDimensionHash, { Smallest: null }]],
+            },
+            true
+          )
+        ).entryHash;
+        await pause(pauseDuration);
+
+        const ratingContextResult: EntryHash[] = await callZomeAlice(
+          "sensemaker",
+          "compute_context",
+          {
+            resource_ehs: [post1, post2, post3, post4],
+            context_eh: ratingContextHash,
+            can_publish_result: false,
+          },
+          true
+        );
+        t.deepEqual(ratingContextResult, [post2, post4, post3, post1]);
+
+        // a context on ratings between 1 and 5, or with an importance of 9 or more, that aren't rated exactly 1 or 0.5
+        const compositeContextHash = new EntryRecord<CulturalContext>(
+          await callZomeAlice(
+            "sensemaker",
+            "create_cultural_context",
+            {
+              name: "middling ratings or important, best rated first",
+              resource_def_eh: resourceDefHash,
+              thresholds: [],
+              order_by: [[ratingDimensionHash, { Biggest: null }]],
+              threshold_condition: {
+                And: [
+                  {
+                    Or: [
+                      {
+                        Threshold: {
+                          dimension_eh: ratingDimensionHash,
+                          kind: { Between: { Float: 5 } },
+                          value: { Float: 1 },
+                        },
+                      },
+                      {
+                        Threshold: {
+                          dimension_eh: importanceDimensionHash,
+                          kind: { GreaterOrEqual: null },
+                          value: { Integer: 9 },
+                        },
+                      },
+                    ],
+                  },
+                  {
+                    Not: {
+                      Threshold: {
+                        dimension_eh: ratingDimensionHash,
+                        kind: { InSet: [{ Float: 0.5 }] },
+                        value: { Float: 1 },
+                      },
+                    },
+                  },
+                ],
+              },
+            },
+            true
+          )
+        ).entryHash;
+        await pause(pauseDuration);
+
+        // post3 (2.5) and post4 (1) are between 1 and 5 but post4 is rated 1, post2 (0.5) and post1 (7.25, importance 5) are outside
+        const compositeContextResult: EntryHash[] = await callZomeAlice(
+          "sensemaker",
+          "compute_context",
+          {
+            resource_ehs: [post1, post2, post3, post4],
+            context_eh: compositeContextHash,
+            can_publish_result: false,
+          },
+          true
+        );
+        t.deepEqual(compositeContextResult, [post3]);
+
+        // When Alice lists the contexts Then all three are found, both overall and for the resource def
+        const contextHashes = [contextHash, ratingContextHash, compositeContextHash].map(encodeHashToBase64).sort();
+        const listedContextHashes = (records: Record[]) =>
+          records.map((record) => encodeHashToBase64(new EntryRecord<CulturalContext>(record).entryHash)).sort();
+        t.deepEqual(
+          listedContextHashes(await callZomeAlice("sensemaker", "get_cultural_contexts", null, true)),
+          contextHashes
+        );
+        t.deepEqual(
+          listedContextHashes(
+            await callZomeAlice("sensemaker", "get_cultural_contexts_for_resource_def", resourceDefHash, true)
+          ),
+          contextHashes
+        );
+
+        // When Alice looks up a context by name within the applet Then only the exact name matches
+        const contextByName = await callZomeAlice(
+          "sensemaker",
+          "get_cultural_context_by_name",
+          { applet_eh, name: "rated above 0, lowest first" },
+          true
+        );
+        t.deepEqual(new EntryRecord<CulturalContext>(contextByName).entryHash, ratingContextHash);
+        t.equal(
+          await callZomeAlice("sensemaker", "get_cultural_context_by_name", { applet_eh, name: "rated" }, true),
+          null
+        );
+        t.equal(
+          await callZomeAlice(
+            "sensemaker",
+            "get_cultural_context_by_name",
+            { applet_eh: await fakeEntryHash(), name: "rated above 0, lowest first" },
+            true
+          ),
+          null
+        );
+
+        // When Alice creates another context with the same name Then looking it up returns the latest one
+        const renamedContextHash = new EntryRecord<CulturalContext>(
+          await callZomeAlice(
+            "sensemaker",
+            "create_cultural_context",
+            {
+              name: "rated above 0, lowest first",
+              resource_def_eh: resourceDefHash,
+              thresholds: [
+                {
+                  dimension_eh: ratingDimensionHash,
+                  kind: { GreaterThan: null },
+                  value: { Float: 0.5 },
+                },
+              ],
+              order_by: [[ratingDimensionHash, { Smallest: null }]],
+            },
+            true
+          )
+        ).entryHash;
+        await pause(pauseDuration);
+        for (let i = 0; i < 3; i++) {
+          const latestContextByName = await callZomeAlice(
+            "sensemaker",
+            "get_cultural_context_by_name",
+            { applet_eh, name: "rated above 0, lowest first" },
+            true
+          );
+          t.deepEqual(new EntryRecord<CulturalContext>(latestContextByName).entryHash, renamedContextHash);
+        }
+      } catch (e) {
+        console.log(e);
+        t.ok(null);
+      }
+
+      await cleanup();
+    });
+  });
+};
@@ -130,38 +130,8 @@ export default () => {
         await pause(pauseDuration);
 
 
-        // Create range and dimensions now not created in register_applet
-        const integerRange = {
-          name: "10-scale",
-          kind: {
-            Integer: { min: 0, max: 10 },
-          },
-        };
-
-        const rangeRecord: Record = await callZomeAlice(
-          "sensemaker",
-          "create_range",
-          integerRange,
-          true
-        );
-        const rangeEntryRecord = new EntryRecord<Range>(rangeRecord);
-        const rangeHash = rangeEntryRecord.entryHash;
-
-        const createDimension = {
-          name: "likeness",
-          range_eh: rangeHash,
-          computed: false,
-        };
-
-        const createDimensionRecord: Record = await callZomeAlice(
-          "sensemaker",
-          "create_dimension",
-          createDimension,
-          true
-        );
-        const likenessDimensionEntryHash = new EntryRecord<Dimension>(
-          createDimensionRecord
-        ).entryHash;
+        // the likeness dimension is created by register_applet from the config
+        const likenessDimensionEntryHash = appletConfig.dimensions["likeness"];
         t.ok(likenessDimensionEntryHash);
 
         // create an assessment on the Post
@@ -0,0 +1,154 @@
+import { EntryHash, encodeHashToBase64, fakeEntryHash } from "@holochain/client";
+import { pause, runScenario } from "@holochain/tryorama";
+import {
+  Assessment,
+  CreateAssessmentInput,
+  Dimension,
+  Method,
+  Range,
+  ResourceDef,
+} from "#client";
+import pkg from "tape-promise/tape";
+import { setUpAliceandBob } from "../../utils";
+import { EntryRecord } from "@holochain-open-dev/utils";
+const { test } = pkg;
+
+export default () => {
+  test("live methods", async (t) => {
+    await runScenario(async (scenario) => {
+      const {
+        alice,
+        cleanup,
+        alice_agent_key,
+        ss_cell_id_alice,
+        provider_cell_id_alice,
+      } = await setUpAliceandBob();
+
+      const callZomeAlice = async (
+        zome_name,
+        fn_name,
+        payload,
+        is_ss = false
+      ) => {
+        return await alice.callZome({
+          cap_secret: null,
+          cell_id: is_ss ? ss_cell_id_alice : provider_cell_id_alice,
+          zome_name,
+          fn_name,
+          payload,
+          provenance: alice_agent_key,
+        });
+      };
+      try {
+        const pauseDuration = 1000;
+        await scenario.shareAllAgents();
+        await pause(pauseDuration);
+
+        const applet_eh = await fakeEntryHash();
+
+        const createPostEntryHash: EntryHash = await callZomeAlice(
+          "test_provider",
+          "create_post",
+          { title: "Intro", content: "a thoughtful post" }
+        );
+        t.ok(createPostEntryHash);
+
+        const tenScaleRangeHash = new EntryRecord<Range>(
+          await callZomeAlice(
+            "sensemaker",
+            "create_range",
+            { name: "10-scale", kind: { Integer: { min: 0, max: 10 } } },
+            true
+          )
+        ).entryHash;
+        const scoreRangeHash = new EntryRecord<Range>(
+          await callZomeAlice(
+            "sensemaker",
+            "create_range",
+            { name: "score", kind: { Integer: { min: 0, max: 100 } } },
+            true
+          )
+        ).entryHash;
+
+        const createDimension = async (name: string, range_eh: EntryHash, computed: boolean) =>
+          new EntryRecord<Dimension>(
+            await callZomeAlice("sensemaker", "create_dimension", { name, range_eh, computed }, true)
+          ).entryHash;
+        const qualityDimensionHash = await createDimension("quality", tenScaleRangeHash, false);
+        const totalQualityDimensionHash = await createDimension("total_quality", scoreRangeHash, true);
+
+        const createResourceDef: ResourceDef = {
+          resource_name: "post",
+          applet_eh,
+          //@ts-ignore
+          base_types: [
+            //@ts-ignore
+            { entry_index: 0, zome_index: 0, visibility: { Public: null } },
+          ],
+          role_name: "test_provider_dna",
+          zome_name: "provider",
+        };
+        const resourceDefHash = new EntryRecord<ResourceDef>(
+          await callZomeAlice("sensemaker", "create_resource_def", createResourceDef, true)
+        ).entryHash;
+
+        // Alice creates a method that is recomputed whenever a quality assessment is committed
+        const totalQualityMethodHash = new EntryRecord<Method>(
+          await callZomeAlice(
+            "sensemaker",
+            "create_method",
+            {
+              name: "total_quality_method",
+              input_dimension_ehs: [qualityDimensionHash],
+              output_dimension_eh: totalQualityDimensionHash,
+              program: { Sum: null },
+              can_compute_live: true,
+              requires_validation: false,
+            },
+            true
+          )
+        ).entryHash;
+        t.ok(totalQualityMethodHash);
+        await pause(pauseDuration);
+
+        const totalQualityValues = async () => {
+          const assessmentsForResources = await callZomeAlice(
+            "sensemaker",
+            "get_assessments_for_resources",
+            {
+              resource_ehs: [createPostEntryHash],
+              dimension_ehs: [totalQualityDimensionHash],
+            },
+            true
+          );
+          const assessments: Assessment[] =
+            assessmentsForResources[encodeHashToBase64(createPostEntryHash)] ?? [];
+          return assessments.map((assessment) => assessment.value);
+        };
+
+        // When Alice assesses the quality of the post Then the total is computed without calling run_method
+        for (const value of [4, 8]) {
+          const createAssessment: CreateAssessmentInput = {
+            value: { Integer: value },
+            dimension_eh: qualityDimensionHash,
+            resource_eh: createPostEntryHash,
+            resource_def_eh: resourceDefHash,
+            maybe_input_dataset: null,
+          };
+          t.ok(await callZomeAlice("sensemaker", "create_assessment", createAssessment, true));
+          await pause(pauseDuration);
+        }
+
+        const values = await totalQualityValues();
+        t.equal(values.length, 2);
+        t.ok(values.find((value) => JSON.stringify(value) === JSON.stringify({ Integer: 4 })));
+        t.ok(values.find((value) => JSON.stringify(value) === JSON.stringify({ Integer: 12 })));
+      } catch (e) {
+        console.log(e);
+        t.ok(null);
+      }
+
+      await cleanup();
+    });
+  });
+};
@@ -1554,7 +1554,7 @@ export default () => {
         // Bob gets the created resource type
         // create an assessment on the Post
         const createAssessment: CreateAssessmentInput = {
-          value: { Float: -2 },
+          value: { Float: 2 },
           dimension_eh: createDimensionEntryHash,
           resource_eh: createPostEntryHash,
           resource_def_eh: createResourceDefEntryHash,
@@ -0,0 +1,309 @@
+import { EntryHash, fakeEntryHash } from "@holochain/client";
+import { pause, runScenario } from "@holochain/tryorama";
+import {
+  Assessment,
+  CreateAssessmentInput,
+  Dimension,
+  Method,
+  Range,
+  ResourceDef,
+} from "#client";
+import pkg from "tape-promise/tape";
+import { setUpAliceandBob } from "../../utils";
+import { EntryRecord } from "@holochain-open-dev/utils";
+const { test } = pkg;
+
+export default () => {
+  test("expression programs", async (t) => {
+    await runScenario(async (scenario) => {
+      const {
+        alice,
+        cleanup,
+        alice_agent_key,
+        ss_cell_id_alice,
+        provider_cell_id_alice,
+      } = await setUpAliceandBob();
+
+      const callZomeAlice = async (
+        zome_name,
+        fn_name,
+        payload,
+        is_ss = false
+      ) => {
+        return await alice.callZome({
+          cap_secret: null,
+          cell_id: is_ss ? ss_cell_id_alice : provider_cell_id_alice,
+          zome_name,
+          fn_name,
+          payload,
+          provenance: alice_agent_key,
+        });
+      };
+      try {
+        const pauseDuration = 1000;
+        await scenario.shareAllAgents();
+        await pause(pauseDuration);
+
+        const applet_eh = await fakeEntryHash();
+
+        const createPostEntryHash: EntryHash = await callZomeAlice(
+          "test_provider",
+          "create_post",
+          { title: "Intro", content: "a thoughtful post" }
+        );
+        t.ok(createPostEntryHash);
+
+        const tenScaleRangeHash = new EntryRecord<Range>(
+          await callZomeAlice(
+            "sensemaker",
+            "create_range",
+            { name: "10-scale", kind: { Integer: { min: 0, max: 10 } } },
+            true
+          )
+        ).entryHash;
+        const scoreRangeHash = new EntryRecord<Range>(
+          await callZomeAlice(
+            "sensemaker",
+            "create_range",
+            { name: "score", kind: { Float: { min: 0, max: 100 } } },
+            true
+          )
+        ).entryHash;
+
+        const createDimension = async (name: string, range_eh: EntryHash, computed: boolean, requires_validation = false) =>
+          new EntryRecord<Dimension>(
+            await callZomeAlice("sensemaker", "create_dimension", { name, range_eh, computed, requires_validation }, true)
+          ).entryHash;
+        const qualityDimensionHash = await createDimension("quality", tenScaleRangeHash, false);
+        const relevanceDimensionHash = await createDimension("relevance", tenScaleRangeHash, false);
+        const scoreDimensionHash = await createDimension("score", scoreRangeHash, true, true);
+        const bestQualityDimensionHash = await createDimension("best_quality", tenScaleRangeHash, true);
+        const ratingDimensionHash = await createDimension("rating", scoreRangeHash, false);
+
+        const createResourceDef: ResourceDef = {
+          resource_name: "post",
+          applet_eh,
+          //@ts-ignore
+          base_types: [
+            //@ts-ignore
+            { entry_index: 0, zome_index: 0, visibility: { Public: null } },
+          ],
+          role_name: "test_provider_dna",
+          zome_name: "provider",
+        };
+        const resourceDefHash = new EntryRecord<ResourceDef>(
+          await callZomeAlice("sensemaker", "create_resource_def", createResourceDef, true)
+        ).entryHash;
+
+        const assessments: Array<[EntryHash, number]> = [
+          [qualityDimensionHash, 4],
+          [qualityDimensionHash, 8],
+          [relevanceDimensionHash, 2],
+        ];
+        const qualityAssessmentHashes: EntryHash[] = [];
+        const relevanceAssessmentHashes: EntryHash[] = [];
+        for (const [dimension_eh, value] of assessments) {
+          const createAssessment: CreateAssessmentInput = {
+            value: { Integer: value },
+            dimension_eh,
+            resource_eh: createPostEntryHash,
+            resource_def_eh: resourceDefHash,
+            maybe_input_dataset: null,
+          };
+          const assessmentHash = new EntryRecord<Assessment>(
+            await callZomeAlice("sensemaker", "create_assessment", createAssessment, true)
+          ).entryHash;
+          (dimension_eh === qualityDimensionHash ? qualityAssessmentHashes : relevanceAssessmentHashes).push(assessmentHash);
+        }
+
+        // Alice creates a weighted score of average quality and average relevance
+        const scoreMethodHash = new EntryRecord<Method>(
+          await callZomeAlice(
+            "sensemaker",
+            "create_method",
+            {
+              name: "score_method",
+              input_dimension_ehs: [qualityDimensionHash, relevanceDimensionHash],
+              output_dimension_eh: scoreDimensionHash,
+              program: {
+                Expression: {
+                  Weighted: [
+                    [0.5, { Average: { Dimension: 0 } }],
+                    [0.25, { Average: { Dimension: 1 } }],
+                  ],
+                },
+              },
+              can_compute_live: false,
+              requires_validation: true,
+            },
+            true
+          )
+        ).entryHash;
+
+        // Alice creates a method that only reports the best quality once the post has been judged relevant
+        const bestQualityMethodHash = new EntryRecord<Method>(
+          await callZomeAlice(
+            "sensemaker",
+            "create_method",
+            {
+              name: "best_quality_method",
+              input_dimension_ehs: [qualityDimensionHash, relevanceDimensionHash],
+              output_dimension_eh: bestQualityDimensionHash,
+              program: {
+                Expression: {
+                  If: {
+                    condition: {
+                      Compare: [
+                        { Count: { Dimension: 1 } },
+                        { GreaterThan: null },
+                        { Constant: { Integer: 0 } },
+                      ],
+                    },
+                    then: { Max: { Dimension: 0 } },
+                    otherwise: { Constant: { Integer: 0 } },
+                  },
+                },
+              },
+              can_compute_live: false,
+              requires_validation: false,
+            },
+            true
+          )
+        ).entryHash;
+
+        // Alice creates a method summing an Integer and a Float dimension, which only has Integer assessments
+        const mixedSumMethodHash = new EntryRecord<Method>(
+          await callZomeAlice(
+            "sensemaker",
+            "create_method",
+            {
+              name: "mixed_sum_method",
+              input_dimension_ehs: [qualityDimensionHash, ratingDimensionHash],
+              output_dimension_eh: scoreDimensionHash,
+              program: { Sum: null },
+              can_compute_live: false,
+              requires_validation: true,
+            },
+            true
+          )
+        ).entryHash;
+
+        // Alice creates a method whose program produces Integers for a Float output dimension but fails
+        try {
+          await callZomeAlice(
+            "sensemaker",
+            "create_method",
+            {
+              name: "mistyped_method",
+              input_dimension_ehs: [qualityDimensionHash],
+              output_dimension_eh: scoreDimensionHash,
+              program: { Expression: { Sum: { Dimension: 0 } } },
+              can_compute_live: false,
+              requires_validation: false,
+            },
+            true
+          );
+          t.fail("method with a mistyped program was committed");
+        } catch (e) {
+          t.ok(JSON.stringify(e).includes("method mistyped_method has an invalid program"));
+        }
+
+        // Alice creates a score method that skips validation but fails, as the score dimension requires it
+        try {
+          await callZomeAlice(
+            "sensemaker",
+            "create_method",
+            {
+              name: "unvalidated_score_method",
+              input_dimension_ehs: [qualityDimensionHash],
+              output_dimension_eh: scoreDimensionHash,
+              program: { Expression: { Average: { Dimension: 0 } } },
+              can_compute_live: false,
+              requires_validation: false,
+            },
+            true
+          );
+          t.fail("method skipping the validation its output dimension requires was committed");
+        } catch (e) {
+          t.ok(JSON.stringify(e).includes("method unvalidated_score_method and its output dimension score must agree on requires_validation"));
+        }
+
+        await pause(pauseDuration);
+
+        const runMethod = async (method_eh: EntryHash) =>
+          new EntryRecord<Assessment>(
+            await callZomeAlice(
+              "sensemaker",
+              "run_method",
+              {
+                resource_eh: createPostEntryHash,
+                resource_def_eh: resourceDefHash,
+                method_eh,
+              },
+              true
+            )
+          ).entry;
+
+        // 0.5 * (4 + 8) / 2 + 0.25 * 2
+        const score = await runMethod(scoreMethodHash);
+        t.deepEqual(score.value, { Float: 3.5 });
+        // the score method requires validation, so the assessments it was computed from are committed with it
+        t.ok(score.maybe_input_dataset);
+        t.equal(score.maybe_input_dataset!.data_points.length, 2);
+        t.equal(score.maybe_input_dataset!.data_points[0][1].length, 2);
+
+        // Alice creates a score that doesn't match the assessments in its dataset but fails
+        try {
+          await callZomeAlice(
+            "sensemaker",
+            "create_assessment",
+            {
+              value: { Float: 10 },
+              dimension_eh: scoreDimensionHash,
+              resource_eh: createPostEntryHash,
+              resource_def_eh: resourceDefHash,
+              maybe_input_dataset: {
+                from: scoreMethodHash,
+                data_points: [
+                  [qualityDimensionHash, qualityAssessmentHashes],
+                  [relevanceDimensionHash, relevanceAssessmentHashes],
+                ],
+              },
+            },
+            true
+          );
+          t.fail("objective assessment with a forged value was committed");
+        } catch (e) {
+          t.ok(JSON.stringify(e).includes("does not match the value"));
+        }
+
+        // Alice creates a score without a dataset but fails
+        try {
+          await callZomeAlice(
+            "sensemaker",
+            "create_assessment",
+            {
+              value: { Float: 3.5 },
+              dimension_eh: scoreDimensionHash,
+              resource_eh: createPostEntryHash,
+              resource_def_eh: resourceDefHash,
+              maybe_input_dataset: null,
+            },
+            true
+          );
+          t.fail("objective assessment without a dataset was committed");
+        } catch (e) {
+          t.ok(JSON.stringify(e).includes("assessments along dimension score need a dataset to be recomputed from"));
+        }
+        t.deepEqual((await runMethod(bestQualityMethodHash)).value, { Integer: 8 });
+        // the sum is of the output dimension's kind even though only Integers were summed
+        t.deepEqual((await runMethod(mixedSumMethodHash)).value, { Float: 12 });
+      } catch (e) {
+        console.log(e);
+        t.ok(null);
+      }
+
+      await cleanup();
+    });
+  });
+};
@@ -0,0 +1,107 @@
+import { EntryHash, encodeHashToBase64, fakeEntryHash } from "@holochain/client";
+import { pause, runScenario } from "@holochain/tryorama";
+import { Assessment, Dimension, Range, ResourceDef } from "#client";
+import pkg from "tape-promise/tape";
+import { setUpAliceandBob } from "../../utils";
+import { EntryRecord } from "@holochain-open-dev/utils";
+const { test } = pkg;
+
+export default () => {
+  test("assessments scoped to resource defs", async (t) => {
+    await runScenario(async (scenario) => {
+      const { alice, cleanup, alice_agent_key, ss_cell_id_alice } = await setUpAliceandBob();
+
+      const callZomeAlice = async (fn_name, payload) => {
+        return await alice.callZome({
+          cap_secret: null,
+          cell_id: ss_cell_id_alice,
+          zome_name: "sensemaker",
+          fn_name,
+          payload,
+          provenance: alice_agent_key,
+        });
+      };
+      try {
+        const pauseDuration = 1000;
+        await scenario.shareAllAgents();
+        await pause(pauseDuration);
+
+        const rangeHash = new EntryRecord<Range>(
+          await callZomeAlice("create_range", { name: "10-scale", kind: { Integer: { min: 0, max: 10 } } })
+        ).entryHash;
+        const createDimension = async (name: string): Promise<EntryHash> =>
+          new EntryRecord<Dimension>(
+            await callZomeAlice("create_dimension", { name, range_eh: rangeHash, computed: false })
+          ).entryHash;
+        const likenessDimensionHash = await createDimension("likeness");
+        const importanceDimensionHash = await createDimension("importance");
+        const createResourceDef = async (resource_name: string): Promise<EntryHash> =>
+          new EntryRecord<ResourceDef>(
+            await callZomeAlice("create_resource_def", {
+              resource_name,
+              applet_eh: await fakeEntryHash(),
+              base_types: [{ entry_index: 0, zome_index: 0, visibility: { Public: null } }],
+              role_name: "test_provider_dna",
+              zome_name: "provider",
+            })
+          ).entryHash;
+        const postResourceDefHash = await createResourceDef("post");
+        const taskResourceDefHash = await createResourceDef("task");
+        await pause(pauseDuration);
+
+        const post = await fakeEntryHash();
+        const task = await fakeEntryHash();
+        const assessments: Array<[EntryHash, EntryHash, EntryHash, number]> = [
+          [post, postResourceDefHash, likenessDimensionHash, 1],
+          [post, postResourceDefHash, importanceDimensionHash, 2],
+          [task, taskResourceDefHash, likenessDimensionHash, 3],
+        ];
+        for (const [resource_eh, resource_def_eh, dimension_eh, value] of assessments) {
+          await callZomeAlice("create_assessment", {
+            value: { Integer: value },
+            dimension_eh,
+            resource_eh,
+            resource_def_eh,
+            maybe_input_dataset: null,
+          });
+        }
+        await pause(pauseDuration);
+
+        const values = (resourceAssessments: Record<string, Assessment[]>) =>
+          Object.values(resourceAssessments)
+            .flat()
+            .map((assessment) => (assessment.value as any).Integer)
+            .sort();
+
+        // all assessments on posts, without the tasks'
+        const postAssessments = await callZomeAlice("get_assessments_for_resources", {
+          resource_def_ehs: [postResourceDefHash],
+        });
+        t.deepEqual(Object.keys(postAssessments), [encodeHashToBase64(post)]);
+        t.deepEqual(values(postAssessments), [1, 2]);
+
+        // all assessments on posts along likeness
+        t.deepEqual(
+          values(
+            await callZomeAlice("get_assessments_for_resources", {
+              resource_def_ehs: [postResourceDefHash],
+              dimension_ehs: [likenessDimensionHash],
+            })
+          ),
+          [1]
+        );
+
+        // the dimension filter is honoured without resource defs too
+        t.deepEqual(
+          values(await callZomeAlice("get_assessments_for_resources", { dimension_ehs: [likenessDimensionHash] })),
+          [1, 3]
+        );
+      } catch (e) {
+        console.log(e);
+        t.ok(null);
+      }
+
+      await cleanup();
+    });
+  });
+};
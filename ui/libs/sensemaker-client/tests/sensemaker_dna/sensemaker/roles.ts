@@ -0,0 +1,158 @@
+import { encodeHashToBase64 } from "@holochain/client";
+import { pause, runScenario } from "@holochain/tryorama";
+import { Dimension, Method, Range, RoleGrant } from "#client";
+import pkg from "tape-promise/tape";
+import { setUpAliceandBob } from "../../utils";
+import { EntryRecord } from "@holochain-open-dev/utils";
+const { test } = pkg;
+
+export default () => {
+  test("role based stewardship", async (t) => {
+    await runScenario(async (scenario) => {
+      const {
+        alice,
+        bob,
+        cleanup,
+        alice_agent_key,
+        bob_agent_key,
+        ss_cell_id_alice,
+        ss_cell_id_bob,
+      } = await setUpAliceandBob();
+
+      const callZomeAlice = async (fn_name, payload) => {
+        return await alice.callZome({
+          cap_secret: null,
+          cell_id: ss_cell_id_alice,
+          zome_name: "sensemaker",
+          fn_name,
+          payload,
+          provenance: alice_agent_key,
+        });
+      };
+      const callZomeBob = async (fn_name, payload) => {
+        return await bob.callZome({
+          cap_secret: null,
+          cell_id: ss_cell_id_bob,
+          zome_name: "sensemaker",
+          fn_name,
+          payload,
+          provenance: bob_agent_key,
+        });
+      };
+      try {
+        const pauseDuration = 1000;
+        await scenario.shareAllAgents();
+        await pause(pauseDuration);
+
+        // alice, the community activator, creates the primitives
+        const rangeHash = new EntryRecord<Range>(
+          await callZomeAlice("create_range", { name: "10-scale", kind: { Integer: { min: 0, max: 10 } } })
+        ).entryHash;
+        const inputDimensionHash = new EntryRecord<Dimension>(
+          await callZomeAlice("create_dimension", { name: "likeness", range_eh: rangeHash, computed: false })
+        ).entryHash;
+        const outputDimensionHash = new EntryRecord<Dimension>(
+          await callZomeAlice("create_dimension", { name: "total_likeness", range_eh: rangeHash, computed: true })
+        ).entryHash;
+        await pause(pauseDuration);
+
+        const method: Method = {
+          name: "total_likeness_method",
+          input_dimension_ehs: [inputDimensionHash],
+          output_dimension_eh: outputDimensionHash,
+          program: { Sum: null },
+          can_compute_live: false,
+          requires_validation: false,
+        };
+
+        // bob has no role yet
+        t.notOk(await callZomeBob("i_have_role", { MethodAuthor: null }));
+        try {
+          await callZomeBob("create_method", method);
+          t.ok(null, "bob should not be able to create a method without the role");
+        } catch (e) {
+          t.ok(JSON.stringify(e).includes("only the community activator or a method author can create this entry"));
+        }
+
+        // only the community activator can grant roles
+        try {
+          await callZomeBob("grant_role", { grantee: bob_agent_key, role: { Steward: null } });
+          t.ok(null, "bob should not be able to grant a role to themselves");
+        } catch (e) {
+          t.ok(JSON.stringify(e).includes("only the community activator can grant roles"));
+        }
+
+        const grantRecord = new EntryRecord<RoleGrant>(
+          await callZomeAlice("grant_role", { grantee: bob_agent_key, role: { MethodAuthor: null } })
+        );
+        t.ok(grantRecord.actionHash);
+        await pause(pauseDuration);
+
+        const bobGrants = await callZomeBob("get_role_grants_for_agent", bob_agent_key);
+        t.equal(bobGrants.length, 1);
+        t.equal(
+          encodeHashToBase64(new EntryRecord<RoleGrant>(bobGrants[0]).actionHash),
+          encodeHashToBase64(grantRecord.actionHash)
+        );
+
+        // alice can't claim a grant made for bob
+        const aliceGrant = new EntryRecord<RoleGrant>(
+          await callZomeAlice("grant_role", { grantee: alice_agent_key, role: { TrayCurator: null } })
+        );
+        await pause(pauseDuration);
+        try {
+          await callZomeBob("claim_role", aliceGrant.actionHash);
+          t.ok(null, "bob should not be able to claim a grant made for alice");
+        } catch (e) {
+          t.ok(JSON.stringify(e).includes("a role grant can only be claimed by its grantee"));
+        }
+
+        // once claimed, bob can create methods but still not dimensions
+        await callZomeBob("claim_role", grantRecord.actionHash);
+        t.ok(await callZomeBob("i_have_role", { MethodAuthor: null }));
+        t.notOk(await callZomeBob("i_have_role", { Steward: null }));
+        const methodRecord = await callZomeBob("create_method", method);
+        t.ok(new EntryRecord<Method>(methodRecord).entryHash);
+        try {
+          await callZomeBob("create_dimension", { name: "urgency", range_eh: rangeHash, computed: false });
+          t.ok(null, "a method author should not be able to create a dimension");
+        } catch (e) {
+          t.ok(JSON.stringify(e).includes("only the community activator or a steward can create this entry"));
+        }
+
+        // the community activator holds every role
+        t.ok(await callZomeAlice("i_have_role", { Steward: null }));
+
+        // only the community activator can revoke a grant, after which bob no longer holds the role
+        try {
+          await callZomeBob("revoke_role", grantRecord.actionHash);
+          t.ok(null, "bob should not be able to revoke a role grant");
+        } catch (e) {
+          t.ok(JSON.stringify(e).includes("only the community activator can revoke roles"));
+        }
+        await callZomeAlice("revoke_role", grantRecord.actionHash);
+        await pause(pauseDuration);
+        t.notOk(await callZomeBob("i_have_role", { MethodAuthor: null }));
+        // validation rejects bob's methods too, once bob has checkpointed alice's chain past the revocation
+        try {
+          await callZomeBob("create_method", { ...method, name: "revoked_likeness_method" });
+          t.ok(null, "bob should not be able to create a method once the grant is revoked");
+        } catch (e) {
+          t.ok(JSON.stringify(e).includes("only the community activator or a method author can create this entry"));
+        }
+        t.deepEqual(await callZomeBob("get_role_grants_for_agent", bob_agent_key), []);
+        try {
+          await callZomeBob("claim_role", grantRecord.actionHash);
+          t.ok(null, "bob should not be able to claim a revoked grant");
+        } catch (e) {
+          t.ok(JSON.stringify(e).includes("the role grant has been revoked"));
+        }
+      } catch (e) {
+        console.log(e);
+        t.ok(null);
+      }
+
+      await cleanup();
+    });
+  });
+};
@@ -0,0 +1,121 @@
+import { encodeHashToBase64, fakeEntryHash } from "@holochain/client";
+import { pause, runScenario } from "@holochain/tryorama";
+import { Assessment, Dimension, Range, ResourceDef } from "#client";
+import pkg from "tape-promise/tape";
+import { setUpAliceandBob } from "../../utils";
+import { EntryRecord } from "@holochain-open-dev/utils";
+const { test } = pkg;
+
+export default () => {
+  test("listing across sharded anchors", async (t) => {
+    await runScenario(async (scenario) => {
+      const {
+        alice,
+        bob,
+        cleanup,
+        alice_agent_key,
+        bob_agent_key,
+        ss_cell_id_alice,
+        ss_cell_id_bob,
+      } = await setUpAliceandBob();
+
+      const callZomeAlice = async (fn_name, payload, zome_name = "sensemaker") => {
+        return await alice.callZome({
+          cap_secret: null,
+          cell_id: ss_cell_id_alice,
+          zome_name,
+          fn_name,
+          payload,
+          provenance: alice_agent_key,
+        });
+      };
+      const callZomeBob = async (fn_name, payload, zome_name = "sensemaker") => {
+        return await bob.callZome({
+          cap_secret: null,
+          cell_id: ss_cell_id_bob,
+          zome_name,
+          fn_name,
+          payload,
+          provenance: bob_agent_key,
+        });
+      };
+      try {
+        const pauseDuration = 1000;
+        await scenario.shareAllAgents();
+        await pause(pauseDuration);
+
+        // enough entries that their links land in several shards
+        const count = 8;
+        const rangeHashes = [];
+        for (let i = 0; i < count; i++) {
+          rangeHashes.push(
+            new EntryRecord<Range>(
+              await callZomeAlice("create_range", { name: `range ${i}`, kind: { Integer: { min: 0, max: 10 + i } } })
+            ).entryHash
+          );
+        }
+        const dimensionHashes = [];
+        for (let i = 0; i < count; i++) {
+          dimensionHashes.push(
+            new EntryRecord<Dimension>(
+              await callZomeAlice("create_dimension", { name: `dimension ${i}`, range_eh: rangeHashes[i], computed: false })
+            ).entryHash
+          );
+        }
+        const resourceDefHash = new EntryRecord<ResourceDef>(
+          await callZomeAlice("create_resource_def", {
+            resource_name: "post",
+            applet_eh: await fakeEntryHash(),
+            base_types: [{ entry_index: 0, zome_index: 0, visibility: { Public: null } }],
+            role_name: "test_provider_dna",
+            zome_name: "provider",
+          })
+        ).entryHash;
+        await pause(pauseDuration);
+
+        const resourceHashes = [];
+        for (let i = 0; i < count; i++) {
+          const resource_eh = await fakeEntryHash();
+          resourceHashes.push(resource_eh);
+          await callZomeAlice("create_assessment", {
+            value: { Integer: i },
+            dimension_eh: dimensionHashes[i],
+            resource_eh,
+            resource_def_eh: resourceDefHash,
+            maybe_input_dataset: null,
+          });
+        }
+        await pause(pauseDuration);
+
+        // every shard is read when listing, by the agent that made the links and by others
+        for (const callZome of [callZomeAlice, callZomeBob]) {
+          t.equal((await callZome("get_ranges", null)).length, count);
+          t.equal((await callZome("get_dimensions", null)).length, count);
+          t.equal((await callZome("get_resource_defs", null)).length, 1);
+          t.equal((await callZome("get_all_assessments", null)).length, count);
+        }
+        const assessmentsForResources: Record<string, Assessment[]> = await callZomeBob("get_assessments_for_resources", {
+          resource_ehs: resourceHashes,
+          dimension_ehs: dimensionHashes,
+        });
+        resourceHashes.forEach((resource_eh, i) => {
+          const assessments = assessmentsForResources[encodeHashToBase64(resource_eh)];
+          t.deepEqual(assessments.map((assessment) => assessment.value), [{ Integer: i }]);
+        });
+
+        const allAgentsForAlice = await callZomeAlice("get_all_agents", null);
+        t.deepEqual(allAgentsForAlice, [bob_agent_key]);
+
+        // nothing was linked from the unsharded anchors, so there is nothing to migrate
+        t.equal(await callZomeAlice("migrate_sharded_anchors", null), 0);
+        t.equal(await callZomeAlice("migrate_sharded_anchors", null, "assessment_tray"), 0);
+        t.equal((await callZomeAlice("get_ranges", null)).length, count);
+      } catch (e) {
+        console.log(e);
+        t.ok(null);
+      }
+
+      await cleanup();
+    });
+  });
+};
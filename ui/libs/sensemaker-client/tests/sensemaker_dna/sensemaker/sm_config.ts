@@ -118,6 +118,16 @@ test("test updating of sensemaker config", async (t) => {
         provenance: alice_agent_key,
       });
     };
+    const callZomeBob = async (fn_name, payload) => {
+      return await bob.callZome({
+        cap_secret: null,
+        cell_id: ss_cell_id_bob,
+        zome_name: "sensemaker",
+        fn_name,
+        payload,
+        provenance: bob_agent_key,
+      });
+    };
 
     try {
       await scenario.shareAllAgents();
@@ -136,7 +146,7 @@ test("test updating of sensemaker config", async (t) => {
       const sensemaker_config_update = {
         original_action_hash: sm_config_action_hash,
         updated_sensemaker_config: {
-          neighbourhood: "Rated Agenda 2",
+          neighbourhood: "Rated Agenda",
           wizard_version: "v0.2",
           community_activator: encodeHashToBase64(alice_agent_key),
         },
@@ -161,6 +171,60 @@ test("test updating of sensemaker config", async (t) => {
         maybe_updated_sm_config.signed_action.hashed.hash;
       t.ok(maybe_sm_config);
       t.deepEqual(updated_sm_config_action_hash, updated_config_ah);
+
+      // only the community activator can update the config
+      try {
+        await callZomeBob("update_sensemaker_config", {
+          original_action_hash: sm_config_action_hash,
+          updated_sensemaker_config: {
+            neighbourhood: "Bob's Agenda",
+            wizard_version: "v0.2",
+            community_activator: encodeHashToBase64(bob_agent_key),
+          },
+        });
+        t.ok(null, "bob should not be able to update the sensemaker config");
+      } catch (e) {
+        t.ok(JSON.stringify(e).includes("only the community activator can create or update the sensemaker config"));
+      }
+
+      // updates have to chain from the original config
+      try {
+        await callZomeAlice(
+          "sensemaker",
+          "update_sensemaker_config",
+          { ...sensemaker_config_update, original_action_hash: updated_config_ah },
+          true
+        );
+        t.ok(null, "an update of an update should be rejected");
+      } catch (e) {
+        t.ok(JSON.stringify(e).includes("sensemaker config updates must update the original config created by the community activator"));
+      }
+
+      const invalidUpdates = [
+        [{ neighbourhood: "  " }, "neighbourhood can't be blank"],
+        [{ wizard_version: "0.3" }, "wizard_version 0.3 should be a"],
+        [{ wizard_version: "v0.0.9" }, "wizard_version v0.0.9 is lower than the replaced config's v0.2"],
+        // the update to v0.2 is the one replaced, not the original config
+        [{ wizard_version: "v0.1" }, "wizard_version v0.1 is lower than the replaced config's v0.2"],
+        [{ neighbourhood: "Rated Agenda 2" }, "the neighbourhood can't be changed from Rated Agenda"],
+        [{ community_activator: encodeHashToBase64(bob_agent_key) }, "the sensemaker config must name its author as the community activator"],
+      ];
+      for (const [fields, error] of invalidUpdates) {
+        try {
+          await callZomeAlice(
+            "sensemaker",
+            "update_sensemaker_config",
+            {
+              original_action_hash: sm_config_action_hash,
+              updated_sensemaker_config: { ...sensemaker_config_update.updated_sensemaker_config, ...(fields as object) },
+            },
+            true
+          );
+          t.ok(null, `update with ${JSON.stringify(fields)} should be rejected`);
+        } catch (e) {
+          t.ok(JSON.stringify(e).includes(error as string));
+        }
+      }
     } catch (e) {
       console.log(e);
       t.ok(null);
@@ -0,0 +1,126 @@
+import { fakeEntryHash } from "@holochain/client";
+import { pause, runScenario } from "@holochain/tryorama";
+import { CulturalContext, Dimension, Method, Range, ResourceDef } from "#client";
+import pkg from "tape-promise/tape";
+import { setUpAliceandBob } from "../../utils";
+import { EntryRecord } from "@holochain-open-dev/utils";
+const { test } = pkg;
+
+export default () => {
+  test("structural validation of sensemaker entries", async (t) => {
+    await runScenario(async (scenario) => {
+      const { alice, cleanup, alice_agent_key, ss_cell_id_alice } = await setUpAliceandBob();
+
+      const callZomeAlice = async (fn_name, payload) => {
+        return await alice.callZome({
+          cap_secret: null,
+          cell_id: ss_cell_id_alice,
+          zome_name: "sensemaker",
+          fn_name,
+          payload,
+          provenance: alice_agent_key,
+        });
+      };
+      const expectInvalid = async (fn_name, payload, error: string) => {
+        try {
+          await callZomeAlice(fn_name, payload);
+          t.ok(null, `${fn_name} should be rejected with: ${error}`);
+        } catch (e) {
+          t.ok(JSON.stringify(e).includes(error), error);
+        }
+      };
+      try {
+        const pauseDuration = 1000;
+        await scenario.shareAllAgents();
+        await pause(pauseDuration);
+
+        // a range's min can't be above its max
+        await expectInvalid(
+          "create_range",
+          { name: "upside down", kind: { Integer: { min: 10, max: 0 } } },
+          "range upside down is invalid: min 10 is greater than max 0"
+        );
+
+        const rangeHash = new EntryRecord<Range>(
+          await callZomeAlice("create_range", { name: "10-scale", kind: { Integer: { min: 0, max: 10 } } })
+        ).entryHash;
+        const subjectiveDimensionHash = new EntryRecord<Dimension>(
+          await callZomeAlice("create_dimension", { name: "likeness", range_eh: rangeHash, computed: false })
+        ).entryHash;
+        const objectiveDimensionHash = new EntryRecord<Dimension>(
+          await callZomeAlice("create_dimension", { name: "total_likeness", range_eh: rangeHash, computed: true })
+        ).entryHash;
+        await pause(pauseDuration);
+
+        // a dimension's range_eh has to resolve to a range
+        await expectInvalid(
+          "create_dimension",
+          { name: "rangeless", range_eh: subjectiveDimensionHash, computed: false },
+          "dimension rangeless has a range_eh that is not a range"
+        );
+
+        // a method's inputs must be subjective and its output objective
+        const method: Method = {
+          name: "total_likeness_method",
+          input_dimension_ehs: [subjectiveDimensionHash],
+          output_dimension_eh: objectiveDimensionHash,
+          program: { Sum: null },
+          can_compute_live: false,
+          requires_validation: false,
+        };
+        await expectInvalid(
+          "create_method",
+          { ...method, input_dimension_ehs: [objectiveDimensionHash] },
+          "method total_likeness_method has objective input dimension total_likeness"
+        );
+        await expectInvalid(
+          "create_method",
+          { ...method, output_dimension_eh: subjectiveDimensionHash },
+          "method total_likeness_method has subjective output dimension likeness"
+        );
+        t.ok(new EntryRecord<Method>(await callZomeAlice("create_method", method)).entryHash);
+
+        const resourceDefHash = new EntryRecord<ResourceDef>(
+          await callZomeAlice("create_resource_def", {
+            resource_name: "post",
+            applet_eh: await fakeEntryHash(),
+            base_types: [{ entry_index: 0, zome_index: 0, visibility: { Public: null } }],
+            role_name: "test_provider_dna",
+            zome_name: "provider",
+          })
+        ).entryHash;
+        await pause(pauseDuration);
+
+        // a cultural context's thresholds and order_by must be on objective dimensions
+        const threshold = { dimension_eh: objectiveDimensionHash, kind: { GreaterThan: null }, value: { Integer: 5 } };
+        const culturalContext: CulturalContext = {
+          name: "most liked",
+          resource_def_eh: resourceDefHash,
+          thresholds: [threshold],
+          order_by: [[objectiveDimensionHash, { Biggest: null }]],
+        };
+        await expectInvalid(
+          "create_cultural_context",
+          { ...culturalContext, thresholds: [{ ...threshold, dimension_eh: subjectiveDimensionHash }] },
+          "cultural context most liked has a threshold on subjective dimension likeness"
+        );
+        await expectInvalid(
+          "create_cultural_context",
+          { ...culturalContext, order_by: [[subjectiveDimensionHash, { Biggest: null }]] },
+          "cultural context most liked orders by subjective dimension likeness"
+        );
+        await expectInvalid(
+          "create_cultural_context",
+          { ...culturalContext, order_by: [[rangeHash, { Biggest: null }]] },
+          "cultural context most liked references a dimension_eh that is not a dimension"
+        );
+        t.ok(new EntryRecord<CulturalContext>(await callZomeAlice("create_cultural_context", culturalContext)).entryHash);
+      } catch (e) {
+        console.log(e);
+        t.ok(null);
+      }
+
+      await cleanup();
+    });
+  });
+};
@@ -0,0 +1,220 @@
+import { Record, encodeHashToBase64 } from "@holochain/client";
+import { pause, runScenario } from "@holochain/tryorama";
+import { decode } from "@msgpack/msgpack";
+import { CommunityActivatorHandover, Range, RoleGrant, StewardApproval } from "#client";
+import pkg from "tape-promise/tape";
+import { setUpAliceandBob } from "../../utils";
+import { EntryRecord } from "@holochain-open-dev/utils";
+const { test } = pkg;
+
+export default () => {
+  test("community activator succession", async (t) => {
+    await runScenario(async (scenario) => {
+      const {
+        alice,
+        bob,
+        cleanup,
+        alice_agent_key,
+        bob_agent_key,
+        ss_cell_id_alice,
+        ss_cell_id_bob,
+      } = await setUpAliceandBob();
+
+      const callZomeAlice = async (fn_name, payload) => {
+        return await alice.callZome({
+          cap_secret: null,
+          cell_id: ss_cell_id_alice,
+          zome_name: "sensemaker",
+          fn_name,
+          payload,
+          provenance: alice_agent_key,
+        });
+      };
+      const callZomeBob = async (fn_name, payload) => {
+        return await bob.callZome({
+          cap_secret: null,
+          cell_id: ss_cell_id_bob,
+          zome_name: "sensemaker",
+          fn_name,
+          payload,
+          provenance: bob_agent_key,
+        });
+      };
+      try {
+        const pauseDuration = 1000;
+        await scenario.shareAllAgents();
+        await pause(pauseDuration);
+
+        const range = { name: "10-scale", kind: { Integer: { min: 0, max: 10 } } };
+
+        // without a steward quorum, only the current community activator can hand over
+        try {
+          await callZomeBob("hand_over_community_activator", { successor: bob_agent_key, steward_approvals: [] });
+          t.ok(null, "bob should not be able to take over the community activator role");
+        } catch (e) {
+          t.ok(JSON.stringify(e).includes("only the current community activator can hand over, no steward quorum is configured"));
+        }
+        try {
+          await callZomeBob("approve_community_activator_handover", bob_agent_key);
+          t.ok(null, "only stewards should be able to approve a handover");
+        } catch (e) {
+          t.ok(JSON.stringify(e).includes("only a steward can approve a community activator handover"));
+        }
+
+        // alice hands over to bob
+        const handoverRecord: Record = await callZomeAlice("hand_over_community_activator", {
+          successor: bob_agent_key,
+          steward_approvals: [],
+        });
+        const handover = new EntryRecord<CommunityActivatorHandover>(handoverRecord);
+        t.equal(encodeHashToBase64(handover.entry.successor), encodeHashToBase64(bob_agent_key));
+        t.notOk(handover.entry.previous_handover_ah);
+        await pause(pauseDuration);
+
+        const currentCommunityActivator = await callZomeBob("get_current_community_activator", null);
+        t.equal(encodeHashToBase64(currentCommunityActivator), encodeHashToBase64(bob_agent_key));
+
+        // alice has handed over, so she no longer acts as the community activator
+        try {
+          await callZomeAlice("create_range", range);
+          t.ok(null, "alice should not be able to create a range after handing over");
+        } catch (e) {
+          t.ok(JSON.stringify(e).includes("only the community activator or a steward can create this entry"));
+        }
+        try {
+          await callZomeAlice("hand_over_community_activator", { successor: alice_agent_key, steward_approvals: [] });
+          t.ok(null, "alice should not be able to hand over again");
+        } catch (e) {
+          t.ok(JSON.stringify(e).includes("only the current community activator can hand over, no steward quorum is configured"));
+        }
+
+        // bob only acts as the community activator once the handover is claimed
+        try {
+          await callZomeBob("create_range", range);
+          t.ok(null, "bob should not be able to create a range before claiming the handover");
+        } catch (e) {
+          t.ok(JSON.stringify(e).includes("only the community activator or a steward can create this entry"));
+        }
+        try {
+          await callZomeAlice("claim_community_activator", handover.actionHash);
+          t.ok(null, "only the successor should be able to claim a handover");
+        } catch (e) {
+          t.ok(JSON.stringify(e).includes("a community activator handover can only be claimed by its successor"));
+        }
+
+        await callZomeBob("claim_community_activator", handover.actionHash);
+        const rangeRecord: Record = await callZomeBob("create_range", range);
+        t.ok(new EntryRecord<Range>(rangeRecord).entryHash);
+        await pause(pauseDuration);
+
+        const latestConfig: Record = await callZomeAlice("get_latest_sensemaker_config", null);
+        const config = decode((latestConfig.entry as any).Present.entry) as any;
+        t.equal(config.community_activator, encodeHashToBase64(bob_agent_key));
+        t.equal(config.neighbourhood, "Rated Agenda");
+
+        // the next handover follows on from bob's
+        const nextHandoverRecord: Record = await callZomeBob("hand_over_community_activator", {
+          successor: alice_agent_key,
+          steward_approvals: [],
+        });
+        const nextHandover = new EntryRecord<CommunityActivatorHandover>(nextHandoverRecord);
+        t.equal(
+          encodeHashToBase64(nextHandover.entry.previous_handover_ah!),
+          encodeHashToBase64(handover.actionHash)
+        );
+        try {
+          await callZomeBob("create_range", { ...range, name: "another 10-scale" });
+          t.ok(null, "bob should not be able to create a range after handing over");
+        } catch (e) {
+          t.ok(JSON.stringify(e).includes("only the community activator or a steward can create this entry"));
+        }
+      } catch (e) {
+        console.log(e);
+        t.ok(null);
+      }
+
+      await cleanup();
+    });
+  });
+
+  test("community activator handover fork", async (t) => {
+    await runScenario(async (scenario) => {
+      const {
+        alice,
+        bob,
+        cleanup,
+        alice_agent_key,
+        bob_agent_key,
+        ss_cell_id_alice,
+        ss_cell_id_bob,
+      } = await setUpAliceandBob(false, undefined, 1);
+
+      const callZomeAlice = async (fn_name, payload) => {
+        return await alice.callZome({
+          cap_secret: null,
+          cell_id: ss_cell_id_alice,
+          zome_name: "sensemaker",
+          fn_name,
+          payload,
+          provenance: alice_agent_key,
+        });
+      };
+      const callZomeBob = async (fn_name, payload) => {
+        return await bob.callZome({
+          cap_secret: null,
+          cell_id: ss_cell_id_bob,
+          zome_name: "sensemaker",
+          fn_name,
+          payload,
+          provenance: bob_agent_key,
+        });
+      };
+      try {
+        const pauseDuration = 1000;
+        await scenario.shareAllAgents();
+        await pause(pauseDuration);
+
+        // bob is a steward, and a quorum of one
+        const grantRecord = new EntryRecord<RoleGrant>(
+          await callZomeAlice("grant_role", { grantee: bob_agent_key, role: { Steward: null } })
+        );
+        await pause(pauseDuration);
+        await callZomeBob("claim_role", grantRecord.actionHash);
+        await pause(pauseDuration);
+
+        // bob approves handing over from alice back to alice, then alice hands over to bob
+        const approval: StewardApproval = await callZomeBob("approve_community_activator_handover", alice_agent_key);
+        t.notOk(approval.previous_handover_ah);
+        const handover = new EntryRecord<CommunityActivatorHandover>(
+          await callZomeAlice("hand_over_community_activator", { successor: bob_agent_key, steward_approvals: [] })
+        );
+        await pause(pauseDuration);
+
+        // the quorum handover would fork the succession, as alice has already handed over from the same handover
+        try {
+          await callZomeBob("hand_over_community_activator", { successor: alice_agent_key, steward_approvals: [approval] });
+          t.ok(null, "a quorum should not be able to hand over from a handover alice has already handed over from");
+        } catch (e) {
+          t.ok(JSON.stringify(e).includes("the community activator has already handed over from this handover"));
+        }
+
+        // so only bob's claim validates
+        await callZomeBob("claim_community_activator", handover.actionHash);
+        await pause(pauseDuration);
+        const currentCommunityActivator = await callZomeAlice("get_current_community_activator", null);
+        t.equal(encodeHashToBase64(currentCommunityActivator), encodeHashToBase64(bob_agent_key));
+        try {
+          await callZomeAlice("create_range", { name: "10-scale", kind: { Integer: { min: 0, max: 10 } } });
+          t.ok(null, "alice should not act as the community activator after handing over");
+        } catch (e) {
+          t.ok(JSON.stringify(e).includes("only the community activator or a steward can create this entry"));
+        }
+      } catch (e) {
+        console.log(e);
+        t.ok(null);
+      }
+
+      await cleanup();
+    });
+  });
+};
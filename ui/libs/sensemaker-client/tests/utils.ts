@@ -38,7 +38,8 @@ export const installAgent = async (
   agentName: string,
   ca_key?: Uint8Array,
   with_config: boolean = false,
-  resource_base_type?: any
+  resource_base_type?: any,
+  steward_quorum?: number
 ) => {
   let agentsHapps: Array<any> = [];
   let appAgentWs: AppAgentWebsocket;
@@ -80,6 +81,7 @@ export const installAgent = async (
                       community_activator: ca_key
                         ? encodeHashToBase64(ca_key)
                         : encodeHashToBase64(agent_key),
+                      steward_quorum,
                     },
                     applet_configs: with_config
                       ? [await sampleAppletConfig(resource_base_type!)]
@@ -157,7 +159,7 @@ export const sampleAppletConfig = async (resource_base_def: AppEntryDef) => {
     applet_eh,
     ranges: [
       { name: "10-scale", kind: { Integer: { min: 0, max: 10 } } },
-      { name: "10-scale", kind: { Integer: { min: 0, max: 1000000 } } },
+      { name: "1000000-scale", kind: { Integer: { min: 0, max: 1000000 } } },
     ],
     dimensions: [
       {
@@ -168,7 +170,7 @@ export const sampleAppletConfig = async (resource_base_def: AppEntryDef) => {
       {
         name: "total_likeness",
         range: {
-          name: "10-scale",
+          name: "1000000-scale",
           kind: { Integer: { min: 0, max: 1000000 } },
         },
         computed: true,
@@ -190,7 +192,7 @@ export const sampleAppletConfig = async (resource_base_def: AppEntryDef) => {
         output_dimension: {
           name: "total_likeness",
           range: {
-            name: "10-scale",
+            name: "1000000-scale",
             kind: {
               Integer: { min: 0, max: 1000000 },
             },
@@ -211,7 +213,7 @@ export const sampleAppletConfig = async (resource_base_def: AppEntryDef) => {
             dimension: {
               name: "total_likeness",
               range: {
-                name: "10-scale",
+                name: "1000000-scale",
                 kind: { Integer: { min: 0, max: 1000000 } },
               },
               computed: true,
@@ -225,7 +227,7 @@ export const sampleAppletConfig = async (resource_base_def: AppEntryDef) => {
             {
               name: "total_likeness",
               range: {
-                name: "10-scale",
+                name: "1000000-scale",
                 kind: {
                   Integer: { min: 0, max: 1000000 },
                 },
@@ -244,7 +246,7 @@ export const sampleAppletConfig = async (resource_base_def: AppEntryDef) => {
             dimension: {
               name: "total_likeness",
               range: {
-                name: "10-scale",
+                name: "1000000-scale",
                 kind: {
                   Integer: { min: 0, max: 1000000 },
                 },
@@ -260,7 +262,7 @@ export const sampleAppletConfig = async (resource_base_def: AppEntryDef) => {
             {
               name: "total_likeness",
               range: {
-                name: "10-scale",
+                name: "1000000-scale",
                 kind: {
                   Integer: { min: 0, max: 1000000 },
                 },
@@ -278,7 +280,8 @@ export const sampleAppletConfig = async (resource_base_def: AppEntryDef) => {
 
 export const setUpAliceandBob = async (
   with_config: boolean = false,
-  resource_base_type?: any
+  resource_base_type?: any,
+  steward_quorum?: number
 ) => {
   const { servicesProcess, signalingServerUrl } = await runLocalServices();
   const alice_conductor = await createConductor(signalingServerUrl);
@@ -294,7 +297,8 @@ export const setUpAliceandBob = async (
     "alice",
     undefined,
     with_config,
-    resource_base_type
+    resource_base_type,
+    steward_quorum
   );
   const {
     appAgentWs: bob,
@@ -307,7 +311,8 @@ export const setUpAliceandBob = async (
     "bob",
     alice_agent_key,
     with_config,
-    resource_base_type
+    resource_base_type,
+    steward_quorum
   );
   await addAllAgentsToAllConductors([alice_conductor, bob_conductor]);
   return {